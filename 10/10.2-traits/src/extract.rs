@@ -0,0 +1,270 @@
+// Extractive summarisation: picking out the sentences of an article that
+// say the most about it, rather than writing new ones.
+//
+// Each sentence gets a score made of three parts:
+//
+//   - TF-IDF. A word counts for more the more often it appears in this
+//     article (term frequency) and the fewer other articles use it (inverse
+//     document frequency). Words like "fish" in an article about fish score
+//     highly; words every article uses don't. The other articles are the
+//     Corpus.
+//   - Position. News articles put the important part first.
+//   - Length. Very short sentences rarely carry much and very long ones are
+//     poor summaries.
+//
+// The best sentences are returned in the order they appear in the article
+// so the summary still reads in order.
+
+use std::collections::{HashMap, HashSet};
+
+// Words too common to say anything about an article.
+const STOP_WORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "for", "from", "had", "has", "have", "he",
+    "her", "his", "i", "in", "is", "it", "its", "me", "my", "of", "on", "or", "she", "so", "that", "the",
+    "their", "them", "they", "this", "to", "too", "was", "we", "were", "will", "with", "you",
+];
+
+// Words ending in a full stop that don't end a sentence.
+const ABBREVIATIONS: &[&str] = &["mr", "mrs", "ms", "dr", "prof", "st", "vs", "etc", "e.g", "i.e", "no"];
+
+// How many words a sentence needs for its length not to count against it.
+const GOOD_LENGTH: usize = 8;
+const TOO_LONG: usize = 40;
+
+/// Split text into sentences at ., ! and ? followed by a space, leaving
+/// abbreviations such as "Dr." and numbers such as "3.5" alone.
+// map_or(true, ..) rather than is_none_or, which only arrived in Rust 1.82.
+#[allow(clippy::unnecessary_map_or)]
+pub fn split_sentences(text: &str) -> Vec<&str> {
+    let mut sentences = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        if !matches!(c, '.' | '!' | '?' | '…') {
+            continue;
+        }
+
+        // Take in any closing quotes or brackets and repeated marks, as in
+        // "Really?!" or (like this.)
+        let mut end = i + c.len_utf8();
+        while let Some(&(j, next)) = chars.peek() {
+            if matches!(next, '.' | '!' | '?' | '"' | '\'' | ')' | '”' | '’') {
+                end = j + next.len_utf8();
+                chars.next();
+            } else {
+                break;
+            }
+        }
+
+        let at_break = chars.peek().map_or(true, |(_, next)| next.is_whitespace());
+        let last_word = text[start..i]
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or("")
+            .trim_start_matches(['(', '"', '\''])
+            .to_lowercase();
+        let abbreviation = c == '.' && ABBREVIATIONS.contains(&last_word.as_str());
+
+        if at_break && !abbreviation {
+            let sentence = text[start..end].trim();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            start = end;
+        }
+    }
+
+    let rest = text[start..].trim();
+    if !rest.is_empty() {
+        sentences.push(rest);
+    }
+    sentences
+}
+
+/// The words in some text, lower case and without stop words.
+pub fn words(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|word| word.trim_matches('\'').to_lowercase())
+        .filter(|word| !word.is_empty() && !STOP_WORDS.contains(&word.as_str()))
+        .collect()
+}
+
+/// The articles that words are compared against, to tell which words are
+/// special to an article and which are just common.
+/// An empty corpus is fine, every word is then treated as equally rare.
+#[derive(Debug, Default)]
+pub struct Corpus {
+    documents: usize,
+    // How many documents each word appears in.
+    document_frequency: HashMap<String, usize>,
+}
+
+impl Corpus {
+    pub fn new<'a>(documents: impl IntoIterator<Item = &'a str>) -> Corpus {
+        let mut corpus = Corpus::default();
+        for document in documents {
+            corpus.add(document);
+        }
+        corpus
+    }
+
+    pub fn add(&mut self, document: &str) {
+        self.documents += 1;
+        let unique: HashSet<String> = words(document).into_iter().collect();
+        for word in unique {
+            *self.document_frequency.entry(word).or_insert(0) += 1;
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.documents
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents == 0
+    }
+
+    // Smoothed so that words the corpus has never seen don't divide by
+    // zero, and words in every document still count for a little.
+    pub fn idf(&self, word: &str) -> f64 {
+        let frequency = self.document_frequency.get(word).copied().unwrap_or(0);
+        ((1 + self.documents) as f64 / (1 + frequency) as f64).ln() + 1.0
+    }
+}
+
+/// How much each part of the score counts. They don't need to add up to 1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Weights {
+    pub tf_idf: f64,
+    pub position: f64,
+    pub length: f64,
+}
+
+impl Default for Weights {
+    fn default() -> Weights {
+        Weights { tf_idf: 0.6, position: 0.25, length: 0.15 }
+    }
+}
+
+/// A score for each sentence of the text.
+pub fn score_sentences<'a>(text: &'a str, corpus: &Corpus, weights: &Weights) -> Vec<(&'a str, f64)> {
+    let sentences = split_sentences(text);
+    let all_words = words(text);
+
+    let mut term_frequency: HashMap<&str, f64> = HashMap::new();
+    for word in &all_words {
+        *term_frequency.entry(word).or_insert(0.0) += 1.0 / all_words.len() as f64;
+    }
+
+    // The average TF-IDF of each sentence's words, so long sentences don't
+    // win just by having more words.
+    let tf_idf: Vec<f64> = sentences
+        .iter()
+        .map(|sentence| {
+            let sentence_words = words(sentence);
+            if sentence_words.is_empty() {
+                return 0.0;
+            }
+            let total: f64 = sentence_words
+                .iter()
+                .map(|word| term_frequency[word.as_str()] * corpus.idf(word))
+                .sum();
+            total / sentence_words.len() as f64
+        })
+        .collect();
+    // Scaled to between 0 and 1 to be comparable with the other parts.
+    let best = tf_idf.iter().cloned().fold(0.0, f64::max);
+
+    sentences
+        .iter()
+        .enumerate()
+        .map(|(i, sentence)| {
+            let tf_idf = if best > 0.0 { tf_idf[i] / best } else { 0.0 };
+            let position = 1.0 - i as f64 / sentences.len() as f64;
+            let length = match sentence.split_whitespace().count() {
+                n if n > TOO_LONG => GOOD_LENGTH as f64 / n as f64,
+                n => n.min(GOOD_LENGTH) as f64 / GOOD_LENGTH as f64,
+            };
+
+            let score = weights.tf_idf * tf_idf + weights.position * position + weights.length * length;
+            (*sentence, score)
+        })
+        .collect()
+}
+
+/// The best count sentences of the text, in their original order.
+pub fn extract<'a>(text: &'a str, corpus: &Corpus, count: usize, weights: &Weights) -> Vec<&'a str> {
+    let scored = score_sentences(text, corpus, weights);
+
+    let mut ranked: Vec<usize> = (0..scored.len()).collect();
+    // Highest score first, and the earlier sentence on a tie.
+    ranked.sort_by(|&a, &b| scored[b].1.total_cmp(&scored[a].1).then(a.cmp(&b)));
+    ranked.truncate(count);
+    ranked.sort_unstable();
+
+    ranked.into_iter().map(|i| scored[i].0).collect()
+}
+
+#[cfg(test)]
+mod extract_tests {
+    use super::*;
+
+    const ARTICLE: &str = "The pond has a new resident. A large golden fish was spotted near the lily pads \
+                           on Tuesday. Dr. Heron, who watches the pond, said the fish is at least 3.5 feet long. \
+                           It was nice weather. Nobody knows where the golden fish came from, but the fish \
+                           seems happy. Lunch was served at noon!";
+
+    #[test]
+    fn test_split_sentences() {
+        let sentences = split_sentences(ARTICLE);
+
+        assert_eq!(sentences.len(), 6);
+        assert_eq!(sentences[2], "Dr. Heron, who watches the pond, said the fish is at least 3.5 feet long.");
+        assert_eq!(sentences[5], "Lunch was served at noon!");
+        assert_eq!(split_sentences("Really?! \"Yes.\" no end"), vec!["Really?!", "\"Yes.\"", "no end"]);
+        assert!(split_sentences("  ").is_empty());
+    }
+
+    #[test]
+    fn test_words() {
+        assert_eq!(words("The fish's tail, AND its fins!"), vec!["fish's", "tail", "fins"]);
+    }
+
+    #[test]
+    fn test_idf() {
+        let corpus = Corpus::new(vec!["the fish swam", "the cat sat", "the cat napped"]);
+
+        assert_eq!(corpus.len(), 3);
+        assert!(corpus.idf("fish") > corpus.idf("cat"));
+        assert!(corpus.idf("otter") > corpus.idf("fish"));
+        assert_eq!(Corpus::default().idf("anything"), 1.0);
+    }
+
+    #[test]
+    fn test_extract_keeps_order_and_prefers_topic() {
+        let corpus = Corpus::new(vec![
+            ARTICLE,
+            "The weather was nice and lunch was served at noon.",
+            "It was nice weather for a walk. Lunch was late.",
+        ]);
+
+        let summary = extract(ARTICLE, &corpus, 2, &Weights::default());
+        assert_eq!(
+            summary,
+            vec![
+                "A large golden fish was spotted near the lily pads on Tuesday.",
+                "Nobody knows where the golden fish came from, but the fish seems happy.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_weights_change_the_choice() {
+        let position_only = Weights { tf_idf: 0.0, position: 1.0, length: 0.0 };
+
+        assert_eq!(extract(ARTICLE, &Corpus::default(), 1, &position_only), vec!["The pond has a new resident."]);
+        assert_eq!(extract(ARTICLE, &Corpus::default(), 10, &position_only).len(), 6);
+        assert!(extract("", &Corpus::default(), 3, &Weights::default()).is_empty());
+    }
+}
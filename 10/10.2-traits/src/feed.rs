@@ -0,0 +1,341 @@
+// Up to now our NewsArticles have been typed out by hand. This module reads
+// them from news feeds instead, in either of the two common formats:
+//
+//     RSS 2.0: <rss><channel><title/><item>...</item></channel></rss>
+//     Atom:    <feed><title/><entry>...</entry></feed>
+//
+// Each item or entry becomes a NewsArticle. The feed's title is used as the
+// article's location as it says where the article came from.
+//
+// The Aggregator collects entries from any number of feeds. The same story
+// often turns up in more than one feed, so entries are matched up by their
+// guid (RSS) or id (Atom) and by their link, and only the first copy is
+// kept. Either one matching is enough, as feeds don't always agree on ids.
+
+mod date;
+
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::xml::{self, Element, XmlError};
+use crate::{NewsArticle, Summary};
+
+pub use date::{format_date, parse_date};
+
+#[derive(Debug)]
+pub enum FeedError {
+    Xml(XmlError),
+    UnknownFormat(String),
+}
+
+impl fmt::Display for FeedError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FeedError::Xml(e) => write!(f, "{}", e),
+            FeedError::UnknownFormat(root) => {
+                write!(f, "<{}> is not an RSS or Atom feed, expected <rss> or <feed>!", root)
+            }
+        }
+    }
+}
+
+impl Error for FeedError {}
+
+impl From<XmlError> for FeedError {
+    fn from(e: XmlError) -> FeedError {
+        FeedError::Xml(e)
+    }
+}
+
+pub struct Entry {
+    // The guid or id, and the link to the article. Both are used to spot
+    // duplicates.
+    pub id: Option<String>,
+    pub link: Option<String>,
+    // As a Unix timestamp.
+    pub published: Option<i64>,
+    pub article: NewsArticle,
+}
+
+fn article(headline: Option<String>, author: Option<String>, location: &str, content: Option<String>) -> NewsArticle {
+    NewsArticle {
+        headline: headline.unwrap_or_else(|| String::from("(untitled)")),
+        author: author.unwrap_or_else(|| String::from("Unknown")),
+        location: location.to_string(),
+        content: content.unwrap_or_default(),
+    }
+}
+
+fn rss_entries(rss: &Element) -> Vec<Entry> {
+    let channel = match rss.child("channel") {
+        Some(channel) => channel,
+        None => return Vec::new(),
+    };
+    let source = channel.child_text("title").unwrap_or_default();
+
+    channel
+        .children_named("item")
+        .map(|item| Entry {
+            id: item.child_text("guid"),
+            link: item.child_text("link"),
+            published: item
+                .child_text("pubDate")
+                .or_else(|| item.child_text("dc:date"))
+                .and_then(|date| parse_date(&date)),
+            article: article(
+                item.child_text("title"),
+                item.child_text("dc:creator").or_else(|| item.child_text("author")),
+                &source,
+                item.child_text("description").or_else(|| item.child_text("content:encoded")),
+            ),
+        })
+        .collect()
+}
+
+// Atom authors are <author><name>...</name></author>.
+fn atom_author(element: &Element) -> Option<String> {
+    element.child("author").and_then(|author| author.child_text("name"))
+}
+
+// An entry can have several links. The one without a rel, or with
+// rel="alternate", is the article itself.
+fn atom_link(entry: &Element) -> Option<String> {
+    entry
+        .children_named("link")
+        .find(|link| matches!(link.attribute("rel"), None | Some("alternate")))
+        .and_then(|link| link.attribute("href"))
+        .map(String::from)
+}
+
+fn atom_entries(feed: &Element) -> Vec<Entry> {
+    let source = feed.child_text("title").unwrap_or_default();
+    let feed_author = atom_author(feed);
+
+    feed.children_named("entry")
+        .map(|entry| Entry {
+            id: entry.child_text("id"),
+            link: atom_link(entry),
+            published: entry
+                .child_text("published")
+                .or_else(|| entry.child_text("updated"))
+                .and_then(|date| parse_date(&date)),
+            article: article(
+                entry.child_text("title"),
+                atom_author(entry).or_else(|| feed_author.clone()),
+                &source,
+                entry.child_text("summary").or_else(|| entry.child_text("content")),
+            ),
+        })
+        .collect()
+}
+
+/// Read the entries from an RSS or Atom document.
+pub fn parse_feed(document: &str) -> Result<Vec<Entry>, FeedError> {
+    let root = xml::parse(document)?;
+
+    match root.name.as_str() {
+        "rss" => Ok(rss_entries(&root)),
+        "feed" => Ok(atom_entries(&root)),
+        other => Err(FeedError::UnknownFormat(other.to_string())),
+    }
+}
+
+#[derive(Default)]
+pub struct Aggregator {
+    entries: Vec<Entry>,
+    seen: HashSet<String>,
+}
+
+impl Aggregator {
+    pub fn new() -> Aggregator {
+        Aggregator::default()
+    }
+
+    /// Add the entries from a feed, skipping any already seen. Returns how
+    /// many were new.
+    pub fn add_feed(&mut self, document: &str) -> Result<usize, FeedError> {
+        let before = self.entries.len();
+
+        for entry in parse_feed(document)? {
+            let keys: Vec<&String> = entry.id.iter().chain(entry.link.iter()).collect();
+            if keys.iter().any(|key| self.seen.contains(*key)) {
+                continue;
+            }
+
+            self.seen.extend(keys.into_iter().cloned());
+            self.entries.push(entry);
+        }
+
+        Ok(self.entries.len() - before)
+    }
+
+    pub fn add_file(&mut self, path: &Path) -> Result<usize, Box<dyn Error>> {
+        let document = fs::read_to_string(path)?;
+        self.add_feed(&document).map_err(|e| format!("{}: {}", path.display(), e).into())
+    }
+
+    /// Newest first, with entries that have no date at the end.
+    pub fn entries(&self) -> Vec<&Entry> {
+        let mut entries: Vec<&Entry> = self.entries.iter().collect();
+        entries.sort_by_key(|entry| std::cmp::Reverse(entry.published));
+        entries
+    }
+
+    /// One line for each entry with its date and summary.
+    pub fn render(&self) -> String {
+        let lines: Vec<String> = self
+            .entries()
+            .iter()
+            .map(|entry| {
+                let date = entry.published.map(format_date).unwrap_or_else(|| String::from("(no date)"));
+                format!("{:<16}  {}", date, entry.article.summarise())
+            })
+            .collect();
+
+        lines.join("\n")
+    }
+}
+
+/// Read every feed file and print what's in them.
+pub fn run(paths: &[String]) -> Result<(), Box<dyn Error>> {
+    let mut aggregator = Aggregator::new();
+
+    for path in paths {
+        aggregator.add_file(Path::new(path))?;
+    }
+
+    println!("{}", aggregator.render());
+    Ok(())
+}
+
+#[cfg(test)]
+mod feed_tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<rss version="2.0" xmlns:dc="http://purl.org/dc/elements/1.1/">
+  <channel>
+    <title>The Pond Times</title>
+    <item>
+      <title>Big Fish Escapes</title>
+      <link>https://pond.example/big-fish</link>
+      <guid>pond-1</guid>
+      <dc:creator>Hungry Cat</dc:creator>
+      <pubDate>Tue, 10 Jun 2003 04:00:00 GMT</pubDate>
+      <description><![CDATA[I saw a <b>big</b> fish today.]]></description>
+    </item>
+    <item>
+      <title>Lily Pads &amp; You</title>
+      <link>https://pond.example/lily-pads</link>
+      <pubDate>Wed, 11 Jun 2003 09:30:00 +0100</pubDate>
+    </item>
+  </channel>
+</rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<feed xmlns="http://www.w3.org/2005/Atom">
+  <title>River News</title>
+  <author><name>Otter</name></author>
+  <entry>
+    <title>Dam Opens</title>
+    <id>urn:river:1</id>
+    <link rel="alternate" href="https://river.example/dam"/>
+    <updated>2003-06-12T08:00:00Z</updated>
+    <summary>The new dam is open.</summary>
+  </entry>
+  <entry>
+    <title>Big Fish Escapes</title>
+    <id>pond-1</id>
+    <published>2003-06-10T04:00:00Z</published>
+    <author><name>Copy Cat</name></author>
+  </entry>
+</feed>"#;
+
+    #[test]
+    fn test_rss() {
+        let entries = parse_feed(RSS).unwrap();
+
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].id.as_deref(), Some("pond-1"));
+        assert_eq!(entries[0].published, Some(1_055_217_600));
+        assert_eq!(entries[0].article.summarise(), "Big Fish Escapes by Hungry Cat (The Pond Times)");
+        assert_eq!(entries[0].article.content, "I saw a <b>big</b> fish today.");
+
+        // No guid and no author.
+        assert_eq!(entries[1].id, None);
+        assert_eq!(entries[1].link.as_deref(), Some("https://pond.example/lily-pads"));
+        assert_eq!(entries[1].article.summarise(), "Lily Pads & You by Unknown (The Pond Times)");
+    }
+
+    #[test]
+    fn test_atom() {
+        let entries = parse_feed(ATOM).unwrap();
+
+        assert_eq!(entries[0].article.summarise(), "Dam Opens by Otter (River News)");
+        assert_eq!(entries[0].published, parse_date("2003-06-12T08:00:00Z"));
+        assert_eq!(entries[0].article.content, "The new dam is open.");
+        assert_eq!(entries[1].article.author, "Copy Cat");
+    }
+
+    #[test]
+    fn test_aggregator_dedupes_and_sorts() {
+        let mut aggregator = Aggregator::new();
+
+        assert_eq!(aggregator.add_feed(RSS).unwrap(), 2);
+        // The second Atom entry is the same story as the first RSS item.
+        assert_eq!(aggregator.add_feed(ATOM).unwrap(), 1);
+        assert_eq!(aggregator.add_feed(RSS).unwrap(), 0);
+
+        assert_eq!(
+            aggregator.render(),
+            "2003-06-12 08:00  Dam Opens by Otter (River News)\n\
+             2003-06-11 08:30  Lily Pads & You by Unknown (The Pond Times)\n\
+             2003-06-10 04:00  Big Fish Escapes by Hungry Cat (The Pond Times)"
+        );
+    }
+
+    #[test]
+    fn test_same_link_with_a_different_guid_is_a_duplicate() {
+        let mut aggregator = Aggregator::new();
+        aggregator.add_feed(RSS).unwrap();
+
+        let copy = "<rss><channel><title>Copies</title><item><title>Big Fish Again</title>\
+                    <guid>copies-99</guid><link>https://pond.example/big-fish</link></item></channel></rss>";
+        assert_eq!(aggregator.add_feed(copy).unwrap(), 0);
+
+        // And a guid already seen is still enough on its own.
+        let relinked = "<rss><channel><title>Copies</title><item><guid>pond-1</guid>\
+                        <link>https://elsewhere.example/fish</link></item></channel></rss>";
+        assert_eq!(aggregator.add_feed(relinked).unwrap(), 0);
+        assert_eq!(aggregator.entries().len(), 2);
+    }
+
+    #[test]
+    fn test_undated_entries_go_last() {
+        let mut aggregator = Aggregator::new();
+        aggregator
+            .add_feed("<rss><channel><title>T</title><item><title>Old</title></item></channel></rss>")
+            .unwrap();
+        aggregator.add_feed(RSS).unwrap();
+
+        assert!(aggregator.render().ends_with("(no date)         Old by Unknown (T)"));
+    }
+
+    #[test]
+    fn test_not_a_feed() {
+        assert!(matches!(parse_feed("<html></html>"), Err(FeedError::UnknownFormat(_))));
+        assert!(matches!(parse_feed("<rss>"), Err(FeedError::Xml(_))));
+    }
+
+    #[test]
+    fn test_example_files() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("feeds");
+        let mut aggregator = Aggregator::new();
+
+        assert_eq!(aggregator.add_file(&dir.join("pond_times.xml")).unwrap(), 3);
+        assert_eq!(aggregator.add_file(&dir.join("river_news.xml")).unwrap(), 2);
+    }
+}
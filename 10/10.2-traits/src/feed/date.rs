@@ -0,0 +1,227 @@
+// The dates found in feeds, turned into Unix timestamps (seconds since the
+// start of 1970 in UTC) so that entries from different feeds can be sorted.
+//
+// RSS uses the RFC 822 format, "Tue, 10 Jun 2003 04:00:00 GMT", and Atom
+// uses RFC 3339, "2003-12-13T18:30:02Z". Both can give a time zone, which
+// is taken off so everything ends up in UTC.
+
+const MONTHS: [&str; 12] = ["Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec"];
+
+// Days since 1970-01-01 for a date in the Gregorian calendar. This is Howard
+// Hinnant's days_from_civil, which works by starting years in March so the
+// leap day falls at the end.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+// The opposite of days_from_civil.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 { shifted_month + 3 } else { shifted_month - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+fn timestamp(date: (i64, i64, i64), time: (i64, i64, i64), offset_minutes: i64) -> Option<i64> {
+    let (year, month, day) = date;
+    let (hour, minute, second) = time;
+
+    // Seconds can be 60 for a leap second. Years are kept to four digits,
+    // which any real feed fits in, so the sums below can't overflow.
+    if !(0..=9999).contains(&year) || !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || second > 60 {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    Some(days * 86_400 + hour * 3600 + minute * 60 + second - offset_minutes * 60)
+}
+
+fn number(text: &str) -> Option<i64> {
+    if text.is_empty() || !text.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+// "+0100", "-05:00" or "Z" to minutes ahead of UTC.
+fn numeric_offset(zone: &str) -> Option<i64> {
+    if zone == "Z" {
+        return Some(0);
+    }
+
+    let sign = match zone.chars().next()? {
+        '+' => 1,
+        '-' => -1,
+        _ => return None,
+    };
+    // The sign is one byte, but anything after it could be a multi-byte
+    // character, so only split in the middle once it is known to be ASCII.
+    let rest = &zone[1..];
+    let (hours, minutes) = match rest.split_once(':') {
+        Some(parts) => parts,
+        None if rest.len() == 4 && rest.is_ascii() => rest.split_at(2),
+        None => return None,
+    };
+    if hours.len() != 2 || minutes.len() != 2 {
+        return None;
+    }
+    Some(sign * (number(hours)? * 60 + number(minutes)?))
+}
+
+/// e.g. "2003-12-13T18:30:02Z", "2003-12-13T18:30:02.25+01:00" or just
+/// "2003-12-13".
+pub fn parse_rfc3339(text: &str) -> Option<i64> {
+    let text = text.trim();
+    let (date, time) = match text.split_once(['T', 't', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (text, None),
+    };
+
+    let mut parts = date.split('-');
+    let year = number(parts.next()?)?;
+    let month = number(parts.next()?)?;
+    let day = number(parts.next()?)?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let time = match time {
+        Some(time) => time,
+        None => return timestamp((year, month, day), (0, 0, 0), 0),
+    };
+
+    let zone_at = time.find(['Z', 'z', '+', '-']).unwrap_or(time.len());
+    let (clock, zone) = time.split_at(zone_at);
+    let offset = if zone.is_empty() { 0 } else { numeric_offset(&zone.to_uppercase())? };
+
+    // Fractions of a second are dropped.
+    let clock = clock.split('.').next()?;
+    let mut parts = clock.split(':');
+    let hour = number(parts.next()?)?;
+    let minute = number(parts.next()?)?;
+    let second = parts.next().map_or(Some(0), number)?;
+
+    timestamp((year, month, day), (hour, minute, second), offset)
+}
+
+/// e.g. "Tue, 10 Jun 2003 04:00:00 GMT" or "10 Jun 03 04:00 +0200".
+pub fn parse_rfc822(text: &str) -> Option<i64> {
+    let mut words: Vec<&str> = text.split_whitespace().collect();
+
+    // The day of the week is optional and not needed.
+    if words.first()?.ends_with(',') {
+        words.remove(0);
+    }
+
+    let (day, month, year, clock, zone) = match words.as_slice() {
+        [day, month, year, clock, zone] => (*day, *month, *year, *clock, *zone),
+        [day, month, year, clock] => (*day, *month, *year, *clock, "GMT"),
+        _ => return None,
+    };
+
+    let day = number(day)?;
+    let month = MONTHS.iter().position(|m| m.eq_ignore_ascii_case(month))? as i64 + 1;
+    let year = match (year.len(), number(year)?) {
+        // Two digit years from old feeds.
+        (2, year) if year < 50 => 2000 + year,
+        (2, year) => 1900 + year,
+        (_, year) => year,
+    };
+
+    let mut parts = clock.split(':');
+    let hour = number(parts.next()?)?;
+    let minute = number(parts.next()?)?;
+    let second = parts.next().map_or(Some(0), number)?;
+
+    let offset = match zone.to_uppercase().as_str() {
+        "GMT" | "UT" | "UTC" | "Z" => 0,
+        "EST" => -5 * 60,
+        "EDT" => -4 * 60,
+        "CST" => -6 * 60,
+        "CDT" => -5 * 60,
+        "MST" => -7 * 60,
+        "MDT" => -6 * 60,
+        "PST" => -8 * 60,
+        "PDT" => -7 * 60,
+        zone => numeric_offset(zone)?,
+    };
+
+    timestamp((year, month, day), (hour, minute, second), offset)
+}
+
+/// Either format, whichever it turns out to be.
+pub fn parse_date(text: &str) -> Option<i64> {
+    parse_rfc3339(text).or_else(|| parse_rfc822(text))
+}
+
+/// "2003-06-10 04:00", in UTC.
+pub fn format_date(timestamp: i64) -> String {
+    let (year, month, day) = civil_from_days(timestamp.div_euclid(86_400));
+    let seconds = timestamp.rem_euclid(86_400);
+
+    format!("{:04}-{:02}-{:02} {:02}:{:02}", year, month, day, seconds / 3600, seconds % 3600 / 60)
+}
+
+#[cfg(test)]
+mod date_tests {
+    use super::*;
+
+    #[test]
+    fn test_rfc3339() {
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(parse_rfc3339("2003-12-13T18:30:02Z"), Some(1_071_340_202));
+        assert_eq!(parse_rfc3339("2003-12-13T18:30:02.25Z"), Some(1_071_340_202));
+        assert_eq!(parse_rfc3339("2003-12-13T19:30:02+01:00"), Some(1_071_340_202));
+        assert_eq!(parse_rfc3339("2003-12-13"), Some(1_071_273_600));
+        assert_eq!(parse_rfc3339("2003-13-13"), None);
+        assert_eq!(parse_rfc3339("yesterday"), None);
+    }
+
+    #[test]
+    fn test_rfc822() {
+        assert_eq!(parse_rfc822("Tue, 10 Jun 2003 04:00:00 GMT"), Some(1_055_217_600));
+        assert_eq!(parse_rfc822("10 Jun 2003 00:00:00 EDT"), Some(1_055_217_600));
+        assert_eq!(parse_rfc822("Tue, 10 Jun 03 06:00 +0200"), Some(1_055_217_600));
+        assert_eq!(parse_rfc822("Tue, 10 Foo 2003 04:00:00 GMT"), None);
+    }
+
+    #[test]
+    fn test_bad_offsets_are_rejected() {
+        assert_eq!(parse_date("Tue, 10 Jun 2003 04:00:00 +1é2"), None);
+        assert_eq!(parse_date("2003-06-10T04:00:00+1é2"), None);
+        assert_eq!(parse_date("2003-06-10T04:00:00+0:100"), None);
+        assert_eq!(parse_date("2003-06-10T04:00:00+01:0x"), None);
+        assert_eq!(parse_date("2003-06-10T06:00:00+0200"), Some(1_055_217_600));
+    }
+
+    #[test]
+    fn test_huge_years_are_rejected() {
+        assert_eq!(parse_date("9999999999999999-01-01"), None);
+        assert_eq!(parse_date("Tue, 10 Jun 999999999999999 04:00:00 GMT"), None);
+        assert_eq!(parse_date("9999-12-31T23:59:59Z"), Some(253_402_300_799));
+    }
+
+    #[test]
+    fn test_round_trip_through_calendar() {
+        for days in [-719_468, -1, 0, 11_016, 18_321, 60_000] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+
+        assert_eq!(format_date(1_055_217_600), "2003-06-10 04:00");
+        assert_eq!(format_date(951_782_400), "2000-02-29 00:00");
+    }
+}
@@ -1,3 +1,7 @@
+// The tutorial code below spells some things out the long way, such as return
+// statements, to show what is going on, so clippy's lints against that are turned off.
+#![allow(clippy::needless_return, clippy::useless_format)]
+
 // Traits are used to tell a compiler that a new Type has a set of specific properties.
 // This is used both in the declaration of functions/objects using generic types and in
 // the definitions of new types.
@@ -52,8 +56,39 @@ pub trait Summary {
     fn summarise(&self) -> String {
         format!("Read More From {}...", self.summarise_author())
     }
+
+    // Default implementations can build on each other too.
+    // summarise_with formats whatever summarise returns, so every type with Summary can
+    // have length limited, Markdown or HTML summaries without writing anything extra.
+    // See summary.rs for the options.
+    fn summarise_with(&self, options: &SummaryOptions) -> String {
+        summary::render(&self.summarise(), self.summary_content(), options)
+    }
+
+    // Longer text that can be shown under the summary. Most types don't have any.
+    fn summary_content(&self) -> Option<&str> {
+        None
+    }
+
+    // The most important sentences of the content, picked out by extract.rs using
+    // the corpus to judge which words matter. Without any content there is nothing to
+    // pick from, so the default falls back on summarise.
+    fn key_sentences(&self, corpus: &Corpus, sentences: usize) -> String {
+        match self.summary_content() {
+            Some(content) if !content.trim().is_empty() => {
+                extract::extract(content, corpus, sentences, &Weights::default()).join(" ")
+            }
+            _ => self.summarise(),
+        }
+    }
 }
 
+mod extract;
+mod summary;
+
+use extract::{Corpus, Weights};
+use summary::{Format, SummaryOptions};
+
 
 // Lets now define types that use this trait.
 // Lets define NewsArticle and Tweet types. 
@@ -77,6 +112,10 @@ impl Summary for NewsArticle {
     fn summarise(&self) -> String {
         format!("{} by {} ({})", &self.headline, &self.author, &self.location)
     }
+
+    fn summary_content(&self) -> Option<&str> {
+        Some(&self.content)
+    }
 }
 
 pub struct Tweet {
@@ -120,6 +159,24 @@ fn summary_demo() {
 
     println!("News Article Summary: {}", news_article.summarise());
     println!("Tweet Summary:        {}", tweet.summarise());
+
+    // The same summaries limited to 20 characters, in Markdown with the article's
+    // content underneath.
+    let options = SummaryOptions {
+        max_length: Some(20),
+        format: Format::Markdown,
+        include_content: true,
+        ..SummaryOptions::default()
+    };
+    println!("\n{}", news_article.summarise_with(&options));
+    println!("\n{}", tweet.summarise_with(&options));
+
+    // And the single most important sentence of each, which for the Tweet is just its
+    // summary as it has no separate content.
+    // An empty corpus treats every word as equally rare.
+    let corpus = Corpus::default();
+    println!("\nKey sentence: {}", news_article.key_sentences(&corpus, 1));
+    println!("Key sentence: {}", tweet.key_sentences(&corpus, 1));
 }
 
 
@@ -237,7 +294,24 @@ impl<T: Display + PartialOrd> Pair<T> {
 // impl<T: Display> _ToString for T {
     
 
+// So far the articles have been written out by hand. feed.rs reads real ones from
+// RSS and Atom files, using the small XML parser in xml.rs.
+// Run with some feed files to see them summarised, e.g.
+//     cargo run -- feeds/pond_times.xml feeds/river_news.xml
+mod feed;
+mod xml;
+
+use std::env;
+use std::process;
+
 fn main() {
-    summary_demo();
-    notify_demo();
+    let feed_paths: Vec<String> = env::args().skip(1).collect();
+
+    if feed_paths.is_empty() {
+        summary_demo();
+        notify_demo();
+    } else if let Err(e) = feed::run(&feed_paths) {
+        eprintln!("Application Error: {}", e);
+        process::exit(1);
+    }
 }
@@ -0,0 +1,166 @@
+// Options for summarise_with, and the formatting it does.
+// summarise on its own can give a string of any length in plain text. This
+// lets a caller ask for a summary that fits in a limited space, marked up as
+// Markdown or HTML, and optionally followed by an excerpt of the content.
+//
+// Lengths are counted in graphemes, what a reader would call characters.
+// A char is not enough: "é" can be two chars (e and a combining accent) and
+// a family emoji is several chars joined together, and cutting between them
+// would leave half a character on the end.
+//
+// Text is shortened before it is escaped, so markup such as &amp; is never
+// cut in half.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+const ELLIPSIS: &str = "…";
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Format {
+    #[default]
+    Plain,
+    Markdown,
+    Html,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SummaryOptions {
+    // The longest the summary line can be, including the ellipsis.
+    pub max_length: Option<usize>,
+    pub format: Format,
+    // Add the start of the content underneath, for types that have any.
+    pub include_content: bool,
+    pub excerpt_length: usize,
+}
+
+impl Default for SummaryOptions {
+    fn default() -> SummaryOptions {
+        SummaryOptions {
+            max_length: None,
+            format: Format::Plain,
+            include_content: false,
+            excerpt_length: 100,
+        }
+    }
+}
+
+/// Shorten text to at most max_length graphemes, ending in an ellipsis if
+/// anything was cut off.
+pub fn truncate(text: &str, max_length: usize) -> String {
+    if text.graphemes(true).count() <= max_length {
+        return text.to_string();
+    }
+    if max_length == 0 {
+        return String::new();
+    }
+
+    let kept: String = text.graphemes(true).take(max_length - 1).collect();
+    format!("{}{}", kept.trim_end(), ELLIPSIS)
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// A backslash before anything Markdown might treat as formatting.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if "\\`*_{}[]<>()#+-.!|".contains(c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Put together a summary line and an optional excerpt of the content.
+pub fn render(summary: &str, content: Option<&str>, options: &SummaryOptions) -> String {
+    let summary = match options.max_length {
+        Some(max_length) => truncate(summary, max_length),
+        None => summary.to_string(),
+    };
+
+    let excerpt = match content {
+        // Line breaks in the content would break the formatting.
+        Some(content) if options.include_content && !content.trim().is_empty() => {
+            let content = content.split_whitespace().collect::<Vec<&str>>().join(" ");
+            Some(truncate(&content, options.excerpt_length))
+        }
+        _ => None,
+    };
+
+    match (options.format, excerpt) {
+        (Format::Plain, None) => summary,
+        (Format::Plain, Some(excerpt)) => format!("{}\n{}", summary, excerpt),
+        (Format::Markdown, None) => format!("**{}**", escape_markdown(&summary)),
+        (Format::Markdown, Some(excerpt)) => {
+            format!("**{}**\n\n> {}", escape_markdown(&summary), escape_markdown(&excerpt))
+        }
+        (Format::Html, None) => format!("<p><strong>{}</strong></p>", escape_html(&summary)),
+        (Format::Html, Some(excerpt)) => format!(
+            "<p><strong>{}</strong></p>\n<blockquote>{}</blockquote>",
+            escape_html(&summary),
+            escape_html(&excerpt),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod summary_tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("Big Fish", 8), "Big Fish");
+        assert_eq!(truncate("Big Fish", 7), "Big Fi…");
+        // The space before the ellipsis is dropped.
+        assert_eq!(truncate("Big Fish", 5), "Big…");
+        assert_eq!(truncate("Big Fish", 1), "…");
+        assert_eq!(truncate("Big Fish", 0), "");
+    }
+
+    #[test]
+    fn test_truncate_keeps_graphemes_whole() {
+        // e followed by a combining acute accent.
+        let cafe = "cafe\u{301} au lait";
+        assert_eq!(truncate(cafe, 5), "cafe\u{301}…");
+
+        let family = "👨‍👩‍👧 picnic";
+        assert_eq!(truncate(family, 2), "👨‍👩‍👧…");
+        assert_eq!(truncate("🇬🇧🇫🇷🇩🇪", 2), "🇬🇧…");
+    }
+
+    #[test]
+    fn test_formats() {
+        let plain = SummaryOptions::default();
+        let markdown = SummaryOptions { format: Format::Markdown, ..SummaryOptions::default() };
+        let html = SummaryOptions { format: Format::Html, ..SummaryOptions::default() };
+
+        assert_eq!(render("Fish & *Chips*", None, &plain), "Fish & *Chips*");
+        assert_eq!(render("Fish & *Chips*", None, &markdown), "**Fish & \\*Chips\\***");
+        assert_eq!(render("Fish & <Chips>", None, &html), "<p><strong>Fish &amp; &lt;Chips&gt;</strong></p>");
+    }
+
+    #[test]
+    fn test_truncates_before_escaping() {
+        let html = SummaryOptions { format: Format::Html, max_length: Some(7), ..SummaryOptions::default() };
+
+        assert_eq!(render("A & B & C", None, &html), "<p><strong>A &amp; B…</strong></p>");
+    }
+
+    #[test]
+    fn test_content_excerpt() {
+        let options = SummaryOptions { include_content: true, excerpt_length: 12, ..SummaryOptions::default() };
+
+        assert_eq!(render("Title", Some("I saw a big\nfish today."), &options), "Title\nI saw a big…");
+        assert_eq!(render("Title", Some("   "), &options), "Title");
+        assert_eq!(render("Title", None, &options), "Title");
+
+        let markdown = SummaryOptions { format: Format::Markdown, ..options };
+        assert_eq!(render("Title", Some("Short."), &markdown), "**Title**\n\n> Short\\.");
+    }
+}
@@ -0,0 +1,361 @@
+// Just enough of an XML parser to read RSS and Atom feeds.
+// It builds a tree of elements and text from a whole document at once.
+//
+// What it handles: elements, attributes in single or double quotes, text,
+// CDATA sections, the five named entities and numeric character references.
+// Comments, processing instructions such as <?xml ...?> and DOCTYPEs are
+// skipped. Namespace prefixes are kept as part of the name, so
+// <dc:creator> is an element called "dc:creator".
+//
+// What it doesn't: DTDs, entities other than the standard ones, or checking
+// that the document is valid beyond the tags matching up.
+
+use std::error::Error;
+use std::fmt;
+
+// Elements and their content are parsed by calling each other, so every
+// level of nesting uses more of the stack. Real feeds are only a few levels
+// deep, and this stops a document of nothing but start tags from
+// overflowing it.
+const MAX_DEPTH: usize = 256;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+impl Element {
+    pub fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements().filter(move |element| element.name == name)
+    }
+
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.elements().find(|element| element.name == name)
+    }
+
+    /// All the text inside the element, including inside its children, with
+    /// whitespace trimmed from the ends.
+    pub fn text(&self) -> String {
+        fn collect(element: &Element, text: &mut String) {
+            for node in &element.children {
+                match node {
+                    Node::Text(t) => text.push_str(t),
+                    Node::Element(child) => collect(child, text),
+                }
+            }
+        }
+
+        let mut text = String::new();
+        collect(self, &mut text);
+        text.trim().to_string()
+    }
+
+    // The text of a child element, if it has one and it isn't empty.
+    pub fn child_text(&self, name: &str) -> Option<String> {
+        self.child(name).map(Element::text).filter(|text| !text.is_empty())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct XmlError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for XmlError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "XML error on line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for XmlError {}
+
+/// Parse a document and return its root element.
+pub fn parse(input: &str) -> Result<Element, XmlError> {
+    let mut parser = Parser { input, pos: 0, depth: 0 };
+
+    parser.skip_misc()?;
+    if !parser.rest().starts_with('<') {
+        return Err(parser.error("Expected the root element"));
+    }
+    let root = parser.element()?;
+
+    parser.skip_misc()?;
+    if parser.pos < input.len() {
+        return Err(parser.error("Unexpected content after the root element"));
+    }
+
+    Ok(root)
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    // In bytes, always on a character boundary.
+    pos: usize,
+    // How many elements the one being parsed is inside.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    fn error(&self, message: &str) -> XmlError {
+        let line = self.input[..self.pos].matches('\n').count() + 1;
+        XmlError { line, message: message.to_string() }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.pos += rest.len() - rest.trim_start().len();
+    }
+
+    // Move past the next occurrence of end, failing with message if there
+    // isn't one. Returns what was skipped over.
+    fn skip_past(&mut self, end: &str, message: &str) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let i = rest.find(end).ok_or_else(|| self.error(message))?;
+        self.pos += i + end.len();
+        Ok(&rest[..i])
+    }
+
+    // Whitespace, comments, processing instructions and DOCTYPEs, which
+    // can appear before and after the root element.
+    fn skip_misc(&mut self) -> Result<(), XmlError> {
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("<?") {
+                self.skip_past("?>", "Unclosed processing instruction")?;
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->", "Unclosed comment")?;
+            } else if rest.starts_with("<!DOCTYPE") {
+                self.skip_past(">", "Unclosed DOCTYPE")?;
+            } else {
+                return Ok(());
+            }
+        }
+    }
+
+    fn name(&mut self) -> Result<&'a str, XmlError> {
+        let rest = self.rest();
+        let len = rest
+            .find(|c: char| c.is_whitespace() || matches!(c, '>' | '/' | '=' | '<'))
+            .unwrap_or(rest.len());
+        if len == 0 {
+            return Err(self.error("Expected a name"));
+        }
+        self.pos += len;
+        Ok(&rest[..len])
+    }
+
+    // Called with pos on the < of a start tag.
+    fn element(&mut self) -> Result<Element, XmlError> {
+        if self.depth >= MAX_DEPTH {
+            return Err(self.error("Elements are nested too deeply"));
+        }
+        self.pos += 1;
+        let name = self.name()?.to_string();
+        let mut attributes = Vec::new();
+
+        loop {
+            self.skip_whitespace();
+            let rest = self.rest();
+            if rest.starts_with("/>") {
+                self.pos += 2;
+                return Ok(Element { name, attributes, children: Vec::new() });
+            }
+            if rest.starts_with('>') {
+                self.pos += 1;
+                break;
+            }
+            if rest.is_empty() {
+                return Err(self.error("Unclosed start tag"));
+            }
+            attributes.push(self.attribute()?);
+        }
+
+        self.depth += 1;
+        let children = self.content(&name);
+        self.depth -= 1;
+
+        Ok(Element { name, attributes, children: children? })
+    }
+
+    fn attribute(&mut self) -> Result<(String, String), XmlError> {
+        let name = self.name()?.to_string();
+        self.skip_whitespace();
+        if !self.rest().starts_with('=') {
+            return Err(self.error("Expected '=' after an attribute name"));
+        }
+        self.pos += 1;
+        self.skip_whitespace();
+
+        let quote = match self.rest().chars().next() {
+            Some(quote @ '"') | Some(quote @ '\'') => quote,
+            _ => return Err(self.error("Expected a quoted attribute value")),
+        };
+        self.pos += 1;
+        let raw = self.skip_past(&quote.to_string(), "Unclosed attribute value")?;
+
+        Ok((name, self.decode(raw)?))
+    }
+
+    // Everything up to and including the end tag of the named element.
+    fn content(&mut self, name: &str) -> Result<Vec<Node>, XmlError> {
+        let mut children = Vec::new();
+        let mut text = String::new();
+
+        loop {
+            let rest = self.rest();
+            if rest.starts_with("</") {
+                self.pos += 2;
+                let end = self.name()?;
+                if end != name {
+                    return Err(self.error(&format!("Expected </{}> but found </{}>", name, end)));
+                }
+                self.skip_whitespace();
+                if !self.rest().starts_with('>') {
+                    return Err(self.error("Expected '>' to finish the end tag"));
+                }
+                self.pos += 1;
+                break;
+            } else if rest.starts_with("<![CDATA[") {
+                self.pos += "<![CDATA[".len();
+                text.push_str(self.skip_past("]]>", "Unclosed CDATA section")?);
+            } else if rest.starts_with("<!--") {
+                self.skip_past("-->", "Unclosed comment")?;
+            } else if rest.starts_with("<?") {
+                self.skip_past("?>", "Unclosed processing instruction")?;
+            } else if rest.starts_with('<') {
+                if !text.is_empty() {
+                    children.push(Node::Text(std::mem::take(&mut text)));
+                }
+                children.push(Node::Element(self.element()?));
+            } else if rest.is_empty() {
+                return Err(self.error(&format!("<{}> is never closed", name)));
+            } else {
+                let len = rest.find('<').unwrap_or(rest.len());
+                self.pos += len;
+                text.push_str(&self.decode(&rest[..len])?);
+            }
+        }
+
+        if !text.is_empty() {
+            children.push(Node::Text(text));
+        }
+        Ok(children)
+    }
+
+    fn decode(&self, raw: &str) -> Result<String, XmlError> {
+        let mut decoded = String::with_capacity(raw.len());
+        let mut rest = raw;
+
+        while let Some(i) = rest.find('&') {
+            decoded.push_str(&rest[..i]);
+            let end = rest[i..].find(';').ok_or_else(|| self.error("Unfinished entity"))?;
+            let entity = &rest[i + 1..i + end];
+
+            let c = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => match entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                    None => entity
+                        .strip_prefix('#')
+                        .and_then(|decimal| decimal.parse().ok())
+                        .and_then(char::from_u32),
+                },
+            };
+
+            decoded.push(c.ok_or_else(|| self.error(&format!("Unknown entity &{};", entity)))?);
+            rest = &rest[i + end + 1..];
+        }
+
+        decoded.push_str(rest);
+        Ok(decoded)
+    }
+}
+
+#[cfg(test)]
+mod xml_tests {
+    use super::*;
+
+    #[test]
+    fn test_elements_attributes_and_text() {
+        let root = parse(
+            "<?xml version=\"1.0\"?>\n<!-- a feed -->\n<feed lang='en' xmlns=\"x\">\
+             <title>Fish &amp; Chips</title><link href=\"http://a/?b=1&amp;c=2\"/>\
+             <dc:creator> Cat </dc:creator></feed>",
+        )
+        .unwrap();
+
+        assert_eq!(root.name, "feed");
+        assert_eq!(root.attribute("lang"), Some("en"));
+        assert_eq!(root.child_text("title"), Some(String::from("Fish & Chips")));
+        assert_eq!(root.child("link").unwrap().attribute("href"), Some("http://a/?b=1&c=2"));
+        assert_eq!(root.child_text("dc:creator"), Some(String::from("Cat")));
+        assert_eq!(root.elements().count(), 3);
+    }
+
+    #[test]
+    fn test_cdata_comments_and_character_references() {
+        let root = parse("<a>x <![CDATA[<b>bold</b> & more]]><!-- no --> &#233;&#x1F41F;</a>").unwrap();
+
+        assert_eq!(root.text(), "x <b>bold</b> & more é🐟");
+    }
+
+    #[test]
+    fn test_nested_text() {
+        let root = parse("<p>Hello <em>big</em> fish</p>").unwrap();
+
+        assert_eq!(root.text(), "Hello big fish");
+        assert_eq!(root.children.len(), 3);
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse("<a><b></a>").unwrap_err().message, "Expected </b> but found </a>");
+        assert_eq!(parse("<a>\n\n<b>").unwrap_err().line, 3);
+        assert!(parse("<a>&nbsp;</a>").unwrap_err().message.contains("&nbsp;"));
+        assert!(parse("<a x=1/>").is_err());
+        assert!(parse("<a/><b/>").is_err());
+        assert!(parse("just text").is_err());
+    }
+
+    #[test]
+    fn test_deep_nesting_is_an_error() {
+        let nested = |depth: usize| format!("{}{}", "<a>".repeat(depth), "</a>".repeat(depth));
+
+        assert!(parse(&nested(MAX_DEPTH)).is_ok());
+        assert_eq!(parse(&nested(MAX_DEPTH + 1)).unwrap_err().message, "Elements are nested too deeply");
+        // Far more than would fit on the stack without the limit.
+        assert!(parse(&"<a>".repeat(200_000)).is_err());
+    }
+}
@@ -0,0 +1,207 @@
+// Arithmetic that says what happens on overflow. A plain a + 2 panics in a
+// debug build and silently wraps around in a release build, so code near
+// the limits of a type behaves differently depending on how it was built.
+//
+// Each operation comes in three versions:
+//     checked     returns an OverflowError instead of overflowing
+//     saturating  stops at the type's MIN or MAX
+//     wrapping    wraps around, as a release build does
+//
+// They work for every integer type through the Integer trait, which just
+// hands over to the methods the standard library already has for each type.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Operation {
+    Add,
+    Sub,
+    Mul,
+}
+
+impl Operation {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Operation::Add => "+",
+            Operation::Sub => "-",
+            Operation::Mul => "*",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OverflowError<T> {
+    pub operation: Operation,
+    pub left: T,
+    pub right: T,
+}
+
+impl<T: fmt::Display> fmt::Display for OverflowError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {} {} overflows!", self.left, self.operation.symbol(), self.right)
+    }
+}
+
+impl<T: fmt::Debug + fmt::Display> Error for OverflowError<T> {}
+
+pub trait Integer: Copy + fmt::Debug + fmt::Display + PartialEq {
+    const MIN: Self;
+    const MAX: Self;
+    const TWO: Self;
+
+    fn checked(self, operation: Operation, other: Self) -> Option<Self>;
+    fn saturating(self, operation: Operation, other: Self) -> Self;
+    fn wrapping(self, operation: Operation, other: Self) -> Self;
+}
+
+macro_rules! impl_integer {
+    ($($t:ty)*) => {
+        $(
+            impl Integer for $t {
+                const MIN: $t = <$t>::MIN;
+                const MAX: $t = <$t>::MAX;
+                const TWO: $t = 2;
+
+                fn checked(self, operation: Operation, other: $t) -> Option<$t> {
+                    match operation {
+                        Operation::Add => self.checked_add(other),
+                        Operation::Sub => self.checked_sub(other),
+                        Operation::Mul => self.checked_mul(other),
+                    }
+                }
+
+                fn saturating(self, operation: Operation, other: $t) -> $t {
+                    match operation {
+                        Operation::Add => self.saturating_add(other),
+                        Operation::Sub => self.saturating_sub(other),
+                        Operation::Mul => self.saturating_mul(other),
+                    }
+                }
+
+                fn wrapping(self, operation: Operation, other: $t) -> $t {
+                    match operation {
+                        Operation::Add => self.wrapping_add(other),
+                        Operation::Sub => self.wrapping_sub(other),
+                        Operation::Mul => self.wrapping_mul(other),
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+pub fn checked<T: Integer>(operation: Operation, left: T, right: T) -> Result<T, OverflowError<T>> {
+    left.checked(operation, right).ok_or(OverflowError { operation, left, right })
+}
+
+pub fn checked_add<T: Integer>(left: T, right: T) -> Result<T, OverflowError<T>> {
+    checked(Operation::Add, left, right)
+}
+
+pub fn checked_sub<T: Integer>(left: T, right: T) -> Result<T, OverflowError<T>> {
+    checked(Operation::Sub, left, right)
+}
+
+pub fn checked_mul<T: Integer>(left: T, right: T) -> Result<T, OverflowError<T>> {
+    checked(Operation::Mul, left, right)
+}
+
+pub fn saturating_add<T: Integer>(left: T, right: T) -> T {
+    left.saturating(Operation::Add, right)
+}
+
+pub fn saturating_sub<T: Integer>(left: T, right: T) -> T {
+    left.saturating(Operation::Sub, right)
+}
+
+pub fn saturating_mul<T: Integer>(left: T, right: T) -> T {
+    left.saturating(Operation::Mul, right)
+}
+
+pub fn wrapping_add<T: Integer>(left: T, right: T) -> T {
+    left.wrapping(Operation::Add, right)
+}
+
+pub fn wrapping_sub<T: Integer>(left: T, right: T) -> T {
+    left.wrapping(Operation::Sub, right)
+}
+
+pub fn wrapping_mul<T: Integer>(left: T, right: T) -> T {
+    left.wrapping(Operation::Mul, right)
+}
+
+// The three versions of add_two.
+pub fn checked_add_two<T: Integer>(a: T) -> Result<T, OverflowError<T>> {
+    checked_add(a, T::TWO)
+}
+
+pub fn saturating_add_two<T: Integer>(a: T) -> T {
+    saturating_add(a, T::TWO)
+}
+
+pub fn wrapping_add_two<T: Integer>(a: T) -> T {
+    wrapping_add(a, T::TWO)
+}
+
+#[cfg(test)]
+mod arithmetic_tests {
+    use super::*;
+
+    // The same checks for every type, right at its limits.
+    macro_rules! boundary_tests {
+        ($($name:ident: $t:ty,)*) => {
+            $(
+                #[test]
+                fn $name() {
+                    assert_eq!(checked_add_two(<$t>::MAX - 2), Ok(<$t>::MAX));
+                    assert_eq!(
+                        checked_add_two(<$t>::MAX),
+                        Err(OverflowError { operation: Operation::Add, left: <$t>::MAX, right: 2 })
+                    );
+                    assert_eq!(saturating_add_two(<$t>::MAX - 1), <$t>::MAX);
+                    assert_eq!(wrapping_add_two(<$t>::MAX), <$t>::MIN + 1);
+
+                    assert!(checked_sub(<$t>::MIN, 1).is_err());
+                    assert_eq!(saturating_sub(<$t>::MIN, 1), <$t>::MIN);
+                    assert_eq!(wrapping_sub(<$t>::MIN, 1), <$t>::MAX);
+
+                    assert!(checked_mul(<$t>::MAX, 2).is_err());
+                    assert_eq!(saturating_mul(<$t>::MAX, 2), <$t>::MAX);
+                    assert_eq!(wrapping_mul(<$t>::MAX, 2), wrapping_add(<$t>::MAX, <$t>::MAX));
+                }
+            )*
+        };
+    }
+
+    boundary_tests! {
+        boundaries_i8: i8,
+        boundaries_i16: i16,
+        boundaries_i32: i32,
+        boundaries_i64: i64,
+        boundaries_i128: i128,
+        boundaries_isize: isize,
+        boundaries_u8: u8,
+        boundaries_u16: u16,
+        boundaries_u32: u32,
+        boundaries_u64: u64,
+        boundaries_u128: u128,
+        boundaries_usize: usize,
+    }
+
+    #[test]
+    fn test_signed_minimum() {
+        assert_eq!(saturating_sub(i32::MIN, 2), i32::MIN);
+        assert_eq!(saturating_mul(i32::MIN, 2), i32::MIN);
+        assert_eq!(checked_mul(i32::MIN, -1), Err(OverflowError { operation: Operation::Mul, left: i32::MIN, right: -1 }));
+        assert_eq!(checked_add_two(-2), Ok(0));
+    }
+
+    #[test]
+    fn test_error_message() {
+        assert_eq!(checked_add_two(u8::MAX).unwrap_err().to_string(), "255 + 2 overflows!");
+        assert_eq!(checked_sub(0u32, 1).unwrap_err().to_string(), "0 - 1 overflows!");
+    }
+}
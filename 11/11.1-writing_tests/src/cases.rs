@@ -0,0 +1,73 @@
+// A macro for table driven tests. Instead of writing a #[test] function for
+// every value we want to check, we write the check once and list the values:
+//
+//     cases! {
+//         add_two: [two: (2, 4), zero: (0, 2), minus_two: (-2, 0)];
+//     }
+//
+// This makes a module called add_two with one test per case, named after the
+// case, so cargo test reports each one on its own, e.g. "add_two::minus_two".
+// The short form above checks that add_two(input) equals expected for each
+// (input, expected) pair.
+//
+// For anything else, give the check as a closure and list the arguments to
+// call it with:
+//
+//     cases! {
+//         can_hold: |a: Rectangle, b: Rectangle, expected: bool| {
+//             assert_eq!(a.can_hold(&b), expected);
+//         } [
+//             larger_holds_smaller: (rect(50, 60), rect(40, 30), true),
+//             smaller_cannot_hold_larger: (rect(40, 30), rect(50, 60), false),
+//         ];
+//     }
+//
+// Every case needs a name. macro_rules! can't make one up from the values,
+// and a name like case_3 doesn't say what went wrong when it fails.
+
+#[macro_export]
+macro_rules! cases {
+    () => {};
+
+    // The short form, turned into the closure form.
+    ($func:ident : [ $($cases:tt)* ] $(; $($more:tt)*)?) => {
+        $crate::cases! {
+            $func: |input, expected| {
+                assert_eq!($func(input), expected, "{}({:?})", stringify!($func), input);
+            } [ $($cases)* ];
+            $($($more)*)?
+        }
+    };
+
+    ($group:ident : |$($arg:ident $(: $ty:ty)?),*| $body:block [ $($cases:tt)* ] $(; $($more:tt)*)?) => {
+        mod $group {
+            #[allow(unused_imports)]
+            use super::*;
+
+            $crate::cases!(@case [|$($arg $(: $ty)?),*| $body] $($cases)*);
+        }
+
+        $crate::cases! { $($($more)*)? }
+    };
+
+    // One case at a time.
+    (@case [$($check:tt)*] $name:ident : ($($args:tt)*) $(, $($rest:tt)*)?) => {
+        #[test]
+        fn $name() {
+            let check = $($check)*;
+            check($($args)*);
+        }
+
+        $crate::cases!(@case [$($check)*] $($($rest)*)?);
+    };
+
+    (@case [$($check:tt)*] ($($args:tt)*) $($rest:tt)*) => {
+        compile_error!(concat!(
+            "every case in cases! needs a name, e.g. `name: ",
+            stringify!(($($args)*)),
+            "`",
+        ));
+    };
+
+    (@case [$($check:tt)*]) => {};
+}
@@ -0,0 +1,367 @@
+// greeting() above only speaks English. This module greets people in the
+// language of their locale, using message catalogs from the locales folder.
+//
+// A catalog is a text file of "key = template" lines, where a template can
+// contain placeholders in braces:
+//
+//     greeting = Hello, {name}!
+//     group.other = Hello to all {count} of you, {names}!
+//
+// When a locale doesn't have a message the next locale in its fallback
+// chain is tried: "fr-CA" tries "fr-CA", then "fr", then the default
+// locale, English unless changed. So a catalog only has to contain the
+// messages that differ from its parent.
+//
+// Groups are greeted with their names joined in the way the language joins
+// lists ("A, B, and C" in English, "A, B et C" in French) and with the
+// plural form the language uses for that many people. A language without
+// plural forms can still greet one person differently with group.single.
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+// The catalogs that come with the crate, so greeting works without needing
+// to find the locales folder at run time.
+const BUILT_IN: [(&str, &str); 4] = [
+    ("en", include_str!("../locales/en.txt")),
+    ("fr", include_str!("../locales/fr.txt")),
+    ("de", include_str!("../locales/de.txt")),
+    ("ja", include_str!("../locales/ja.txt")),
+];
+
+#[derive(Debug, PartialEq)]
+pub enum GreetingError {
+    // A line in a catalog that isn't "key = template".
+    BadLine { locale: String, line: usize },
+    // No locale in the chain has the message.
+    MissingKey { locale: String, key: String },
+    BadTemplate { key: String, message: String },
+}
+
+impl fmt::Display for GreetingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            GreetingError::BadLine { locale, line } => {
+                write!(f, "Line {} of the {} catalog should look like 'key = template'!", line, locale)
+            }
+            GreetingError::MissingKey { locale, key } => {
+                write!(f, "No message '{}' for {} or any locale it falls back to!", key, locale)
+            }
+            GreetingError::BadTemplate { key, message } => write!(f, "Message '{}': {}!", key, message),
+        }
+    }
+}
+
+impl Error for GreetingError {}
+
+// "fr_FR" and "FR-fr" are both written "fr-FR".
+fn normalise(locale: &str) -> String {
+    let mut parts = locale.trim().split(['-', '_']);
+    let language = parts.next().unwrap_or("").to_lowercase();
+    let rest: Vec<String> = parts.map(str::to_uppercase).collect();
+
+    std::iter::once(language).chain(rest).collect::<Vec<String>>().join("-")
+}
+
+// Which plural form a language uses for a number, following the CLDR
+// rules for the languages we have. French counts 0 as singular and
+// Japanese doesn't have plurals at all.
+fn plural_category(locale: &str, count: usize) -> &'static str {
+    let language = locale.split('-').next().unwrap_or("");
+    match language {
+        "ja" | "zh" | "ko" => "other",
+        "fr" if count <= 1 => "one",
+        _ if count == 1 => "one",
+        _ => "other",
+    }
+}
+
+// Replace each {placeholder} with its value. Braces can be written {{ and }}.
+fn fill(key: &str, template: &str, values: &[(&str, &str)]) -> Result<String, GreetingError> {
+    let error = |message: String| GreetingError::BadTemplate { key: key.to_string(), message };
+    let mut result = String::with_capacity(template.len());
+    let mut chars = template.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.as_str().starts_with('{') => {
+                chars.next();
+                result.push('{');
+            }
+            '}' if chars.as_str().starts_with('}') => {
+                chars.next();
+                result.push('}');
+            }
+            '{' => {
+                let rest = chars.as_str();
+                let end = rest.find('}').ok_or_else(|| error(String::from("'{' is never closed")))?;
+                let name = &rest[..end];
+                let value = values
+                    .iter()
+                    .find(|(placeholder, _)| *placeholder == name)
+                    .ok_or_else(|| error(format!("unknown placeholder {{{}}}", name)))?;
+
+                result.push_str(value.1);
+                chars = rest[end + 1..].chars();
+            }
+            '}' => return Err(error(String::from("'}' without a '{'"))),
+            c => result.push(c),
+        }
+    }
+
+    Ok(result)
+}
+
+#[derive(Debug)]
+pub struct Catalogs {
+    messages: HashMap<String, HashMap<String, String>>,
+    default_locale: String,
+}
+
+impl Catalogs {
+    pub fn new(default_locale: &str) -> Catalogs {
+        Catalogs { messages: HashMap::new(), default_locale: normalise(default_locale) }
+    }
+
+    /// The catalogs in the locales folder of this crate, with English as
+    /// the default.
+    pub fn built_in() -> Catalogs {
+        let mut catalogs = Catalogs::new("en");
+        for (locale, contents) in BUILT_IN.iter() {
+            // These are checked by the tests so can't fail.
+            catalogs.add(locale, contents).unwrap();
+        }
+        catalogs
+    }
+
+    /// Load every LOCALE.txt file in a folder.
+    pub fn load_dir(dir: &Path, default_locale: &str) -> Result<Catalogs, Box<dyn Error>> {
+        let mut catalogs = Catalogs::new(default_locale);
+
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some("txt") {
+                continue;
+            }
+            if let Some(locale) = path.file_stem().and_then(|s| s.to_str()) {
+                catalogs.add(locale, &fs::read_to_string(&path)?)?;
+            }
+        }
+
+        Ok(catalogs)
+    }
+
+    /// Add the messages in a catalog to a locale, replacing any it already
+    /// had with the same keys.
+    pub fn add(&mut self, locale: &str, contents: &str) -> Result<(), GreetingError> {
+        let locale = normalise(locale);
+        let mut parsed = Vec::new();
+
+        for (i, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            match line.split_once('=') {
+                Some((key, template)) if !key.trim().is_empty() => {
+                    parsed.push((key.trim().to_string(), template.trim().to_string()))
+                }
+                _ => return Err(GreetingError::BadLine { locale, line: i + 1 }),
+            }
+        }
+
+        self.messages.entry(locale).or_default().extend(parsed);
+        Ok(())
+    }
+
+    /// The locales to try in turn for a locale, most specific first.
+    pub fn fallback_chain(&self, locale: &str) -> Vec<String> {
+        let locale = normalise(locale);
+        let parts: Vec<&str> = locale.split('-').collect();
+        let mut chain: Vec<String> = (1..=parts.len()).rev().map(|n| parts[..n].join("-")).collect();
+
+        if !chain.contains(&self.default_locale) {
+            chain.push(self.default_locale.clone());
+        }
+        chain
+    }
+
+    pub fn greeter(&self, locale: &str) -> Greeter<'_> {
+        Greeter { catalogs: self, locale: normalise(locale), chain: self.fallback_chain(locale) }
+    }
+}
+
+pub struct Greeter<'a> {
+    catalogs: &'a Catalogs,
+    locale: String,
+    chain: Vec<String>,
+}
+
+impl Greeter<'_> {
+    fn lookup(&self, key: &str) -> Option<&str> {
+        self.chain
+            .iter()
+            .filter_map(|locale| self.catalogs.messages.get(locale))
+            .find_map(|messages| messages.get(key))
+            .map(String::as_str)
+    }
+
+    // The first of the keys that some locale in the chain has. Every key is
+    // tried in the most specific locale before falling back to the next.
+    fn message(&self, keys: &[&str], values: &[(&str, &str)]) -> Result<String, GreetingError> {
+        for key in keys {
+            if let Some(template) = self.lookup(key) {
+                return fill(key, template, values);
+            }
+        }
+
+        Err(GreetingError::MissingKey { locale: self.locale.clone(), key: keys[0].to_string() })
+    }
+
+    pub fn greet(&self, name: &str) -> Result<String, GreetingError> {
+        self.message(&["greeting"], &[("name", name)])
+    }
+
+    /// Join names into a list such as "Ann, Bob, and Cy", using the patterns
+    /// list.two for exactly two names, or list.start, list.middle and
+    /// list.end for the first, middle and last joins of a longer list.
+    pub fn format_list(&self, names: &[&str]) -> Result<String, GreetingError> {
+        let join = |key: &str, first: &str, second: &str| self.message(&[key], &[("0", first), ("1", second)]);
+
+        match names {
+            [] => Ok(String::new()),
+            [name] => Ok(name.to_string()),
+            [first, second] => join("list.two", first, second),
+            [first, middle @ .., second_last, last] => {
+                let mut joined = join("list.end", second_last, last)?;
+                for name in middle.iter().rev() {
+                    joined = join("list.middle", name, &joined)?;
+                }
+                join("list.start", first, &joined)
+            }
+        }
+    }
+
+    /// Greet a group of people at once, using the language's plural form
+    /// for the size of the group.
+    pub fn greet_all(&self, names: &[&str]) -> Result<String, GreetingError> {
+        let list = self.format_list(names)?;
+        let count = names.len().to_string();
+        let values = [("names", list.as_str()), ("count", count.as_str())];
+        let plural = format!("group.{}", plural_category(&self.locale, names.len()));
+
+        match names {
+            [] => self.message(&["group.none", &plural, "group.other"], &values),
+            // {name} as well so group.single can be written like greeting.
+            [name] => self.message(
+                &["group.single", &plural, "group.other"],
+                &[("name", name), ("names", list.as_str()), ("count", count.as_str())],
+            ),
+            _ => self.message(&[&plural, "group.other"], &values),
+        }
+    }
+}
+
+#[cfg(test)]
+mod greetings_tests {
+    use super::*;
+
+    fn catalogs() -> Catalogs {
+        Catalogs::built_in()
+    }
+
+    cases! {
+        greet: |locale: &str, expected: &str| {
+            assert_eq!(catalogs().greeter(locale).greet("Sam").unwrap(), expected);
+        } [
+            english: ("en", "Hello, Sam!"),
+            french: ("fr", "Bonjour, Sam !"),
+            german: ("de", "Hallo, Sam!"),
+            japanese: ("ja", "こんにちは、Samさん！"),
+            falls_back_to_language: ("fr_CA", "Bonjour, Sam !"),
+            falls_back_to_default: ("eo", "Hello, Sam!"),
+        ];
+
+        format_list: |locale: &str, names: &[&str], expected: &str| {
+            assert_eq!(catalogs().greeter(locale).format_list(names).unwrap(), expected);
+        } [
+            no_names: ("en", &[], ""),
+            one_name: ("en", &["Ann"], "Ann"),
+            two_names: ("en", &["Ann", "Bo"], "Ann and Bo"),
+            english_serial_comma: ("en", &["Ann", "Bo", "Cy", "Di"], "Ann, Bo, Cy, and Di"),
+            french: ("fr", &["Ann", "Bo", "Cy"], "Ann, Bo et Cy"),
+            german: ("de", &["Ann", "Bo", "Cy"], "Ann, Bo und Cy"),
+            japanese_three: ("ja", &["Ann", "Bo", "Cy"], "Ann、Bo、Cy"),
+            japanese_two: ("ja", &["Ann", "Bo"], "AnnとBo"),
+        ];
+
+        greet_all: |locale: &str, names: &[&str], expected: &str| {
+            assert_eq!(catalogs().greeter(locale).greet_all(names).unwrap(), expected);
+        } [
+            nobody: ("en", &[], "Hello, everyone!"),
+            one_person: ("en", &["Ann"], "Hello, Ann!"),
+            english_plural: ("en", &["Ann", "Bo", "Cy"], "Hello to all 3 of you, Ann, Bo, and Cy!"),
+            french_plural: ("fr", &["Ann", "Bo"], "Bonjour à vous 2 : Ann et Bo !"),
+            german_plural: ("de", &["Ann", "Bo"], "Hallo an alle 2: Ann und Bo!"),
+            japanese_one_person: ("ja", &["Ann"], "こんにちは、Annさん！"),
+            japanese_plural: ("ja", &["Ann", "Bo"], "AnnとBoの皆さん、こんにちは！"),
+        ];
+    }
+
+    #[test]
+    fn test_fallback_chain() {
+        let catalogs = Catalogs::new("en");
+
+        assert_eq!(catalogs.fallback_chain("fr_ca"), vec!["fr-CA", "fr", "en"]);
+        assert_eq!(catalogs.fallback_chain("en-GB"), vec!["en-GB", "en"]);
+    }
+
+    #[test]
+    fn test_regional_catalog_overrides_some_keys() {
+        let mut catalogs = catalogs();
+        catalogs.add("en-AU", "greeting = G'day, {name}!").unwrap();
+        let greeter = catalogs.greeter("en-AU");
+
+        assert_eq!(greeter.greet("Sam").unwrap(), "G'day, Sam!");
+        assert_eq!(greeter.format_list(&["A", "B"]).unwrap(), "A and B");
+    }
+
+    #[test]
+    fn test_missing_key() {
+        let mut catalogs = Catalogs::new("en");
+        catalogs.add("en", "list.two = {0} & {1}").unwrap();
+
+        assert_eq!(
+            catalogs.greeter("de").greet("Sam"),
+            Err(GreetingError::MissingKey { locale: String::from("de"), key: String::from("greeting") })
+        );
+    }
+
+    #[test]
+    fn test_bad_catalogs_and_templates() {
+        let mut catalogs = Catalogs::new("en");
+
+        assert_eq!(
+            catalogs.add("en", "# fine\ngreeting Hello"),
+            Err(GreetingError::BadLine { locale: String::from("en"), line: 2 })
+        );
+
+        catalogs.add("en", "greeting = Hello, {nmae}!").unwrap();
+        assert!(catalogs.greeter("en").greet("Sam").unwrap_err().to_string().contains("unknown placeholder {nmae}"));
+
+        assert_eq!(fill("k", "{{{name}}}", &[("name", "x")]).unwrap(), "{x}");
+        assert!(fill("k", "Hello {name", &[("name", "x")]).is_err());
+    }
+
+    #[test]
+    fn test_load_dir_matches_built_in() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("locales");
+        let loaded = Catalogs::load_dir(&dir, "en").unwrap();
+
+        assert_eq!(loaded.messages, catalogs().messages);
+    }
+}
@@ -15,6 +15,16 @@
 // Also note the use of the expected argument to tell the tester exactly what to look out 
 // for in a panic message to ensure that the program has panicked for the right reason. 
 
+// Many of the tests below check the same thing for several values. Rather than copy
+// the test out for each one, the cases! macro from cases.rs writes the tests for us.
+// It has to be declared before it is used, hence it being up here.
+#[macro_use]
+mod cases;
+
+// Hand picked values only go so far, so some of the tests below use property.rs to
+// check that something holds for lots of random values instead.
+pub mod property;
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -35,6 +45,7 @@ mod tests {
 // Below we are reimplementing our Rectangle struct with the can_hold method to demo this.
 
 #[allow(dead_code)]
+#[derive(Debug, Clone)]
 struct Rectangle {
     width: u32,
     length: u32,
@@ -48,34 +59,69 @@ impl Rectangle {
 }
 
 // We can then write a set of test functions for the Rectangle.
+// Each case is a pair of rectangles and whether the first can hold the second.
 #[cfg(test)]
 mod rectangle_tests {
     use super::*;
 
-    fn get_test_rectangles() -> (Rectangle, Rectangle) {
-        let larger = Rectangle{
-            width: 50,
-            length: 60,
-        };
-        
-        let smaller = Rectangle {
-            width: 40,
-            length: 30,
-        };
-
-        return (larger, smaller)
+    fn rect(width: u32, length: u32) -> Rectangle {
+        Rectangle { width, length }
+    }
+
+    cases! {
+        can_hold: |a: Rectangle, b: Rectangle, expected: bool| {
+            assert_eq!(a.can_hold(&b), expected);
+        } [
+            larger_can_hold_smaller: (rect(50, 60), rect(40, 30), true),
+            smaller_cannot_hold_larger: (rect(40, 30), rect(50, 60), false),
+            same_size_cannot_hold: (rect(50, 60), rect(50, 60), false),
+            same_height_cannot_hold: (rect(50, 60), rect(40, 60), false),
+            wider_cannot_be_held: (rect(50, 60), rect(60, 10), false),
+        ];
+    }
+}
+
+// Properties of can_hold that should be true for any rectangles at all.
+// Random rectangles come from this generator, which shrinks a failing rectangle by
+// shrinking its width and length.
+#[cfg(test)]
+mod rectangle_properties {
+    use super::*;
+    use crate::property::{check, ints, pairs, Generator, Ints, Random};
+
+    struct Rectangles {
+        sides: Ints<u32>,
+    }
+
+    fn rectangles() -> Rectangles {
+        Rectangles { sides: ints(0..=1000) }
+    }
+
+    impl Generator for Rectangles {
+        type Value = Rectangle;
+
+        fn generate(&self, random: &mut Random, size: u32) -> Rectangle {
+            Rectangle {
+                width: self.sides.generate(random, size),
+                length: self.sides.generate(random, size),
+            }
+        }
+
+        fn shrink(&self, value: &Rectangle) -> Vec<Rectangle> {
+            let widths = self.sides.shrink(&value.width).into_iter().map(|width| Rectangle { width, ..*value });
+            let lengths = self.sides.shrink(&value.length).into_iter().map(|length| Rectangle { length, ..*value });
+            widths.chain(lengths).collect()
+        }
     }
 
     #[test]
-    fn larger_can_hold_smaller() {
-        let (larger, smaller) = get_test_rectangles();   
-        assert!(larger.can_hold(&smaller));
+    fn rectangle_can_never_hold_itself() {
+        check(&rectangles(), |r| !r.can_hold(r));
     }
 
     #[test]
-    fn smaller_cannot_hold_larger() { 
-        let (larger, smaller) = get_test_rectangles();   
-        assert!(!smaller.can_hold(&larger));
+    fn rectangles_cannot_hold_each_other() {
+        check(&pairs(rectangles(), rectangles()), |(a, b)| !(a.can_hold(b) && b.can_hold(a)));
     }
 }
 
@@ -89,13 +135,38 @@ pub fn add_two(a: i32) -> i32 {
     a + 2 
 }
 
+// add_two overflows for the two largest i32s, panicking in a debug build and wrapping
+// around in a release build. arithmetic.rs has checked, saturating and wrapping
+// versions of it for every integer type, with tests right at each type's limits.
+// The test_organisation crate in 11.3 builds the same file for its integration tests.
+pub mod arithmetic;
+
 #[cfg(test)]
 mod tests_add_two {
     use super::add_two;
 
-    #[test] 
-    fn it_adds_two() {
-        assert_eq!(add_two(2), 4);
+    cases! {
+        add_two: [two: (2, 4), zero: (0, 2), minus_two: (-2, 0), large_negative: (-100, -98)];
+    }
+
+    // add_two overflows for the last two i32s so they are left out here.
+    #[test]
+    fn add_two_adds_two() {
+        use crate::property::{check, ints};
+
+        check(&ints(i32::MIN..=i32::MAX - 2), |&a| add_two(a) - a == 2);
+    }
+
+    // checked_add_two works for every i32, failing only where add_two would overflow.
+    #[test]
+    fn checked_add_two_adds_two_or_fails() {
+        use crate::arithmetic::checked_add_two;
+        use crate::property::{any_int, check};
+
+        check(&any_int::<i32>(), |&a| match checked_add_two(a) {
+            Ok(b) => b - a == 2,
+            Err(_) => a > i32::MAX - 2,
+        });
     }
 }
 
@@ -109,19 +180,29 @@ pub fn greeting(name: &str) -> String {
     format!("Hello, {}!", name)
 }
 
+// For greetings in other languages, and for greeting groups of people, see greetings.rs.
+pub mod greetings;
+
 #[cfg(test)]
 mod tests_greeting {
     use super::greeting;
-    
-    #[test]
-    fn greeting_not_empty() {
-        let result = greeting("Bill");
-        assert_ne!(result, String::from(""), "Returned string was empty.");
+
+    cases! {
+        greeting_not_empty: |name: &str| {
+            let result = greeting(name);
+            assert_ne!(result, String::from(""), "Returned string was empty.");
+        } [name: ("Bill"), empty_name: ("")];
+
+        greeting_contains_name: |name: &str| {
+            let result = greeting(name);
+            assert!(result.contains(name), "Greeting did not contain the name, greeting was: {}", result);
+        } [ascii_name: ("Bill"), accented_name: ("Zoë"), name_with_space: ("Mary Jane")];
     }
 
     #[test]
-    fn greeting_contains_name() {
-        let result = greeting("Bill");
-        assert!(result.contains("Bill"), "Greeting did not contain the name, greeting was: {}", result);
+    fn greeting_contains_any_name() {
+        use crate::property::{check, strings};
+
+        check(&strings(40), |name| greeting(name).contains(name.as_str()));
     }
 }
@@ -0,0 +1,420 @@
+// A small property testing library. Instead of checking a function against a
+// few hand picked values, a property test states something that should
+// always be true, such as "add_two(a) - a == 2", and checks it against lots
+// of randomly generated values.
+//
+// When a value is found that breaks the property it is shrunk: simpler
+// values are tried (smaller numbers, shorter strings) for as long as they
+// still fail, so the failure that is reported is as easy to understand as
+// possible.
+//
+// Every run prints nothing unless it fails, in which case the panic message
+// includes the seed that was used. Setting PROPERTY_SEED to that seed runs
+// exactly the same values again. PROPERTY_CASES changes how many values are
+// tried, 100 by default.
+
+use std::cell::Cell;
+use std::env;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::Once;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const DEFAULT_CASES: u32 = 100;
+// Give up shrinking after this many steps in case it never settles.
+const MAX_SHRINK_STEPS: u32 = 1000;
+
+thread_local! {
+    // Set while a property is running so that its panics, which only mean
+    // the property failed, aren't printed.
+    static QUIET: Cell<bool> = const { Cell::new(false) };
+}
+
+static INSTALL_HOOK: Once = Once::new();
+
+// catch_unwind stops a panic but the panic hook has already printed it by
+// then, so a hook is installed that stays quiet for properties. Tests run on
+// several threads at once, so the flag is per thread rather than swapping
+// the hook in and out.
+fn catch_quietly<T>(f: impl FnOnce() -> T) -> Option<T> {
+    INSTALL_HOOK.call_once(|| {
+        let default = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if !QUIET.with(Cell::get) {
+                default(info);
+            }
+        }));
+    });
+
+    QUIET.with(|quiet| quiet.set(true));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    QUIET.with(|quiet| quiet.set(false));
+    result.ok()
+}
+
+// A xorshift random number generator. It isn't good enough for anything
+// that matters but it is plenty for picking test values and, unlike the rand
+// crate, needs nothing adding to Cargo.toml.
+#[derive(Debug, Clone)]
+pub struct Random {
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u64) -> Random {
+        // Zero would get stuck at zero forever.
+        Random { state: seed.max(1) }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+        self.state
+    }
+
+    // A number between low and high inclusive.
+    pub fn between(&mut self, low: i128, high: i128) -> i128 {
+        let span = (high - low) as u128 + 1;
+        let random = ((self.next_u64() as u128) << 64) | self.next_u64() as u128;
+        low + (random % span) as i128
+    }
+
+    // True about once in every n calls.
+    // is_multiple_of would need Rust 1.87, which nothing else here does.
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn one_in(&mut self, n: u64) -> bool {
+        self.next_u64() % n == 0
+    }
+}
+
+/// Something that can make random values of a type and suggest simpler
+/// versions of a value.
+pub trait Generator {
+    type Value: Clone + Debug;
+
+    // size grows from 1 as more cases are tried, so the first values are
+    // small and simple. Generators can ignore it.
+    fn generate(&self, random: &mut Random, size: u32) -> Self::Value;
+
+    // Simpler values to try in place of one that failed, simplest first.
+    fn shrink(&self, value: &Self::Value) -> Vec<Self::Value>;
+}
+
+// The integer types are all handled as i128, which can hold any of them.
+pub trait Int: Copy + Debug {
+    const MIN: Self;
+    const MAX: Self;
+    fn to_i128(self) -> i128;
+    fn from_i128(value: i128) -> Self;
+}
+
+macro_rules! impl_int {
+    ($($t:ty)*) => {
+        $(
+            impl Int for $t {
+                const MIN: $t = <$t>::MIN;
+                const MAX: $t = <$t>::MAX;
+
+                fn to_i128(self) -> i128 {
+                    self as i128
+                }
+
+                fn from_i128(value: i128) -> $t {
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_int!(i8 i16 i32 i64 isize u8 u16 u32 u64 usize);
+
+pub struct Ints<T> {
+    low: i128,
+    high: i128,
+    marker: PhantomData<T>,
+}
+
+/// Integers from the range. The ends of the range and zero are picked more
+/// often than the rest, as that is where bugs tend to be.
+pub fn ints<T: Int>(range: RangeInclusive<T>) -> Ints<T> {
+    let (low, high) = (range.start().to_i128(), range.end().to_i128());
+    assert!(low <= high, "The range of ints is empty");
+    Ints { low, high, marker: PhantomData }
+}
+
+/// Any value of the integer type.
+pub fn any_int<T: Int>() -> Ints<T> {
+    ints(T::MIN..=T::MAX)
+}
+
+impl<T: Int> Ints<T> {
+    // Values shrink towards zero, or whichever end of the range is nearest.
+    fn target(&self) -> i128 {
+        0.clamp(self.low, self.high)
+    }
+}
+
+impl<T: Int> Generator for Ints<T> {
+    type Value = T;
+
+    fn generate(&self, random: &mut Random, _size: u32) -> T {
+        let value = if random.one_in(8) {
+            let edges = [self.low, self.high, self.target(), self.low + 1, self.high - 1];
+            edges[random.between(0, 4) as usize].clamp(self.low, self.high)
+        } else {
+            random.between(self.low, self.high)
+        };
+        T::from_i128(value)
+    }
+
+    // The target, then halfway there, a quarter of the way and so on down to
+    // one step away.
+    fn shrink(&self, value: &T) -> Vec<T> {
+        let value = value.to_i128();
+        let target = self.target();
+        let mut candidates = Vec::new();
+        let mut distance = value - target;
+
+        while distance != 0 {
+            candidates.push(T::from_i128(value - distance));
+            distance /= 2;
+        }
+
+        candidates
+    }
+}
+
+// Mostly plain letters, with some characters that often catch out code that
+// assumes every character is one byte.
+const CHARACTERS: &[char] = &['a', 'b', 'c', 'x', 'y', 'z', 'A', 'Z', '0', '9', ' ', ',', '!', 'é', 'ß', 'ñ', '日', '本', '😀'];
+
+pub struct Strings {
+    max_len: usize,
+}
+
+/// Strings of up to max_len characters.
+pub fn strings(max_len: usize) -> Strings {
+    Strings { max_len }
+}
+
+impl Generator for Strings {
+    type Value = String;
+
+    fn generate(&self, random: &mut Random, size: u32) -> String {
+        let len = random.between(0, self.max_len.min(size as usize) as i128) as usize;
+        (0..len)
+            .map(|_| CHARACTERS[random.between(0, CHARACTERS.len() as i128 - 1) as usize])
+            .collect()
+    }
+
+    // Shorter strings first, then the same string with a character made
+    // simpler.
+    fn shrink(&self, value: &String) -> Vec<String> {
+        let chars: Vec<char> = value.chars().collect();
+        let mut candidates = Vec::new();
+
+        if !chars.is_empty() {
+            candidates.push(String::new());
+            candidates.push(chars[..chars.len() / 2].iter().collect());
+            candidates.push(chars[chars.len() / 2..].iter().collect());
+        }
+        for i in 0..chars.len() {
+            let mut shorter = chars.clone();
+            shorter.remove(i);
+            candidates.push(shorter.into_iter().collect());
+        }
+        for i in 0..chars.len() {
+            if chars[i] != 'a' {
+                let mut simpler = chars.clone();
+                simpler[i] = 'a';
+                candidates.push(simpler.into_iter().collect());
+            }
+        }
+
+        candidates.dedup();
+        candidates.retain(|candidate| candidate != value);
+        candidates
+    }
+}
+
+pub struct Pairs<A, B> {
+    first: A,
+    second: B,
+}
+
+/// Pairs of values from two generators, for properties that need more than
+/// one input.
+pub fn pairs<A: Generator, B: Generator>(first: A, second: B) -> Pairs<A, B> {
+    Pairs { first, second }
+}
+
+impl<A: Generator, B: Generator> Generator for Pairs<A, B> {
+    type Value = (A::Value, B::Value);
+
+    fn generate(&self, random: &mut Random, size: u32) -> Self::Value {
+        (self.first.generate(random, size), self.second.generate(random, size))
+    }
+
+    // Shrink one side at a time, keeping the other as it was.
+    fn shrink(&self, (a, b): &Self::Value) -> Vec<Self::Value> {
+        let firsts = self.first.shrink(a).into_iter().map(|a| (a, b.clone()));
+        let seconds = self.second.shrink(b).into_iter().map(|b| (a.clone(), b));
+        firsts.chain(seconds).collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Failure<T> {
+    pub seed: u64,
+    pub cases: u32,
+    pub original: T,
+    pub shrunk: T,
+}
+
+pub struct Checker {
+    pub seed: u64,
+    pub cases: u32,
+}
+
+impl Checker {
+    // Uses PROPERTY_SEED and PROPERTY_CASES if they are set, otherwise a
+    // seed from the clock.
+    pub fn from_env() -> Checker {
+        let seed = env::var("PROPERTY_SEED")
+            .ok()
+            .and_then(|seed| seed.parse().ok())
+            .unwrap_or_else(|| {
+                SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|time| time.as_nanos() as u64)
+                    .unwrap_or(1)
+            });
+        let cases = env::var("PROPERTY_CASES")
+            .ok()
+            .and_then(|cases| cases.parse().ok())
+            .unwrap_or(DEFAULT_CASES);
+
+        Checker { seed, cases }
+    }
+
+    pub fn with_seed(seed: u64) -> Checker {
+        Checker { seed, cases: DEFAULT_CASES }
+    }
+
+    /// Try the property on generated values and return the first failure,
+    /// shrunk as far as it will go.
+    /// A property fails if it returns false or panics.
+    pub fn run<G, P>(&self, generator: &G, property: P) -> Result<(), Failure<G::Value>>
+    where
+        G: Generator,
+        P: Fn(&G::Value) -> bool,
+    {
+        let holds = |value: &G::Value| {
+            catch_quietly(|| property(value)).unwrap_or(false)
+        };
+        let mut random = Random::new(self.seed);
+
+        for case in 0..self.cases {
+            let value = generator.generate(&mut random, case + 1);
+            if holds(&value) {
+                continue;
+            }
+
+            let mut shrunk = value.clone();
+            for _ in 0..MAX_SHRINK_STEPS {
+                match generator.shrink(&shrunk).into_iter().find(|candidate| !holds(candidate)) {
+                    Some(smaller) => shrunk = smaller,
+                    None => break,
+                }
+            }
+
+            return Err(Failure { seed: self.seed, cases: case + 1, original: value, shrunk });
+        }
+
+        Ok(())
+    }
+}
+
+/// Check a property, panicking with the smallest failing value if it
+/// doesn't hold.
+pub fn check<G, P>(generator: &G, property: P)
+where
+    G: Generator,
+    P: Fn(&G::Value) -> bool,
+{
+    if let Err(failure) = Checker::from_env().run(generator, property) {
+        panic!(
+            "Property failed after {} cases for {:?} (shrunk from {:?}). \
+             Run with PROPERTY_SEED={} to repeat.",
+            failure.cases, failure.shrunk, failure.original, failure.seed,
+        );
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use super::*;
+
+    #[test]
+    fn test_same_seed_same_values() {
+        let generator = pairs(any_int::<i64>(), strings(20));
+        let mut first = Random::new(42);
+        let mut second = Random::new(42);
+
+        for size in 1..50 {
+            assert_eq!(generator.generate(&mut first, size), generator.generate(&mut second, size));
+        }
+    }
+
+    #[test]
+    fn test_ints_stay_in_range() {
+        let mut random = Random::new(7);
+        let generator = ints(-3i8..=5);
+
+        for _ in 0..1000 {
+            assert!((-3..=5).contains(&generator.generate(&mut random, 1)));
+        }
+        assert!(Checker::with_seed(1).run(&any_int::<u64>(), |_| true).is_ok());
+    }
+
+    #[test]
+    fn test_ints_shrink_to_smallest_failure() {
+        let failure = Checker::with_seed(3).run(&any_int::<i32>(), |&x| x < 1000).unwrap_err();
+        assert_eq!(failure.shrunk, 1000);
+
+        // Shrinks towards the end of the range nearest zero.
+        let failure = Checker::with_seed(3).run(&ints(50u8..=200), |&x| x < 120).unwrap_err();
+        assert_eq!(failure.shrunk, 120);
+
+        let failure = Checker::with_seed(3).run(&ints(-200i16..=-50), |&x| x > -120).unwrap_err();
+        assert_eq!(failure.shrunk, -120);
+    }
+
+    #[test]
+    fn test_strings_shrink() {
+        let failure = Checker::with_seed(5).run(&strings(30), |s| s.chars().count() < 4).unwrap_err();
+        assert_eq!(failure.shrunk, "aaaa");
+    }
+
+    #[test]
+    fn test_pairs_shrink_both_sides() {
+        let generator = pairs(ints(0..=1000), ints(0..=1000));
+        let failure = Checker::with_seed(9).run(&generator, |&(a, b)| a + b < 100).unwrap_err();
+
+        assert_eq!(failure.shrunk.0 + failure.shrunk.1, 100);
+    }
+
+    #[test]
+    fn test_panics_count_as_failures() {
+        // Overflow panics in a debug build, which is what tests use.
+        let failure = Checker::with_seed(11).run(&any_int::<i32>(), |&x| x + 10 > x).unwrap_err();
+        assert_eq!(failure.shrunk, i32::MAX - 9);
+
+        let failure = Checker::with_seed(11).run(&ints(0..=100i32), |&x| 100 / (x - 37) != 1000).unwrap_err();
+        assert_eq!(failure.shrunk, 37);
+    }
+}
@@ -28,6 +28,32 @@ fn private_add_two(a: i32) -> i32 {
     a + 2
 }
 
+// The overflow-aware versions of add_two are written and unit tested in the
+// writing_tests crate. Rather than keep a second copy in step, the #[path] attribute
+// builds that same file as a module of this crate, so tests/adder_test.rs can check
+// it through the public API the way any other crate would use it.
+#[path = "../../11.1-writing_tests/src/arithmetic.rs"]
+pub mod arithmetic;
+
+use std::io::{self, Write};
+
+// Write a table of add_two results, with the error in place of the result where it
+// would overflow. Taking any Write rather than printing means the integration tests
+// can check the output, see ../tests/golden_test.rs.
+pub fn write_add_two_table<W: Write + ?Sized>(out: &mut W, inputs: &[i32]) -> io::Result<()> {
+    writeln!(out, "{:>12} | {:>12}", "a", "a + 2")?;
+    writeln!(out, "{}", "-".repeat(27))?;
+
+    for &a in inputs {
+        match arithmetic::checked_add_two(a) {
+            Ok(result) => writeln!(out, "{:>12} | {:>12}", a, result)?,
+            Err(e) => writeln!(out, "{:>12} | {}", a, e)?,
+        }
+    }
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod private_tests_demo {
 
@@ -4,8 +4,12 @@
 
 // First we have to import the code we want to test.
 // The use of the crate title brings the contents of the crate root into scope.
+#[allow(clippy::single_component_path_imports)]
 use test_organisation;
 
+// A use statement can also bring items from further down the crate into scope directly.
+use test_organisation::arithmetic::{checked_add_two, saturating_add_two, wrapping_add_two, Operation, OverflowError};
+
 // Because this is already a separate module from the rest of the library we don't
 // need to wrap any of these tests into a module explicitly and we don't have to
 // add the #[cfg(test)] attribute anywhere.
@@ -14,3 +18,17 @@ use test_organisation;
 fn test_add_two() {
     assert_eq!(test_organisation::add_two(1), 3);
 }
+
+// Integration tests only see the public API, so these check that the arithmetic
+// module is usable from outside the crate, right at the edge of i32.
+#[test]
+fn test_add_two_near_max() {
+    assert_eq!(test_organisation::add_two(i32::MAX - 2), i32::MAX);
+    assert_eq!(checked_add_two(i32::MAX - 2), Ok(i32::MAX));
+    assert_eq!(
+        checked_add_two(i32::MAX - 1),
+        Err(OverflowError { operation: Operation::Add, left: i32::MAX - 1, right: 2 })
+    );
+    assert_eq!(saturating_add_two(i32::MAX - 1), i32::MAX);
+    assert_eq!(wrapping_add_two(i32::MAX - 1), i32::MIN);
+}
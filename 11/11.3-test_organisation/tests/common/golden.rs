@@ -0,0 +1,101 @@
+// Golden file testing. Output is compared with a file in tests/golden that
+// holds what it is expected to be. This is easier than writing out long
+// expected strings in the tests, and changes to the output show up as
+// changes to the golden files when the code is reviewed.
+//
+// After changing the output on purpose, run the tests with
+//
+//     UPDATE_GOLDEN=1 cargo test
+//
+// to write the new output into the golden files, then check the diff.
+//
+// Other crates use this file too, through #[path], so that every crate's
+// golden files live in tests/golden and are updated the same way. Golden
+// files are named with the extension of what they hold, e.g. table.txt or
+// layout.svg, so they can be opened as that kind of file.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+pub const UPDATE_VARIABLE: &str = "UPDATE_GOLDEN";
+
+pub fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(name)
+}
+
+/// Whether a value of UPDATE_GOLDEN asks for the golden files to be
+/// rewritten. Unset, empty, 0, false, no and off all mean no, so
+/// UPDATE_GOLDEN=0 doesn't overwrite anything.
+pub fn update_requested(value: Option<&str>) -> bool {
+    match value {
+        None => false,
+        Some(value) => !matches!(value.trim().to_lowercase().as_str(), "" | "0" | "false" | "no" | "off"),
+    }
+}
+
+/// Compare the output with the golden file, or overwrite the golden file
+/// with it if update is set. The error says where they first differ.
+pub fn compare(path: &Path, actual: &str, update: bool) -> Result<(), String> {
+    if update {
+        return fs::write(path, actual).map_err(|e| format!("Could not write {}: {}", path.display(), e));
+    }
+
+    let expected = fs::read_to_string(path).map_err(|e| {
+        format!(
+            "Could not read {}: {}. Run with {}=1 to create it.",
+            path.display(),
+            e,
+            UPDATE_VARIABLE,
+        )
+    })?;
+    // Git may have checked the file out with Windows line endings.
+    let expected = expected.replace("\r\n", "\n");
+
+    if expected == actual {
+        return Ok(());
+    }
+
+    // Every line matching but the whole not matching can only mean the
+    // newlines on the end are different, which wouldn't show up below.
+    if expected.trim_end_matches('\n') == actual.trim_end_matches('\n') {
+        let newlines = |text: &str| text.len() - text.trim_end_matches('\n').len();
+        return Err(format!(
+            "Output only differs from {} in the newlines at the end: expected {}, actual {}.\n\
+             Run with {}=1 to update it if the change is intended.",
+            path.display(),
+            newlines(&expected),
+            newlines(actual),
+            UPDATE_VARIABLE,
+        ));
+    }
+
+    let mut expected_lines = expected.lines();
+    let mut actual_lines = actual.lines();
+    let mut line = 1;
+    loop {
+        match (expected_lines.next(), actual_lines.next()) {
+            (Some(e), Some(a)) if e == a => line += 1,
+            (e, a) => {
+                return Err(format!(
+                    "Output does not match {} from line {}:\n  expected: {:?}\n  actual:   {:?}\n\
+                     Run with {}=1 to update it if the change is intended.",
+                    path.display(),
+                    line,
+                    e.unwrap_or("<end of file>"),
+                    a.unwrap_or("<end of output>"),
+                    UPDATE_VARIABLE,
+                ));
+            }
+        }
+    }
+}
+
+/// Check output against tests/golden/NAME.
+pub fn assert_golden(name: &str, actual: &str) {
+    let update = update_requested(env::var(UPDATE_VARIABLE).ok().as_deref());
+
+    if let Err(message) = compare(&golden_path(name), actual, update) {
+        panic!("{}", message);
+    }
+}
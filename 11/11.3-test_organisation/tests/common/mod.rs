@@ -0,0 +1,24 @@
+// Code shared between the integration tests.
+// It lives in tests/common/mod.rs rather than tests/common.rs because cargo
+// treats every .rs file directly in tests as a crate of tests of its own.
+// Files in subfolders are left alone, so this only gets compiled into the
+// test files that ask for it with `mod common;`.
+
+// Each test file is its own crate and most only use some of what is in here,
+// which would otherwise give dead code warnings in the rest.
+#![allow(dead_code)]
+
+pub mod golden;
+
+use std::io::{self, Write};
+
+/// Run something that writes output and return what it wrote, so output that
+/// would normally go to stdout can be checked by a test.
+pub fn capture<F>(write: F) -> String
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    let mut buffer: Vec<u8> = Vec::new();
+    write(&mut buffer).expect("writing to a Vec can't fail");
+    String::from_utf8(buffer).expect("output was not UTF-8")
+}
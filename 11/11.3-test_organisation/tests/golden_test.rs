@@ -0,0 +1,59 @@
+// Tests that check output against golden files, using the helpers shared
+// with the other test files in tests/common.
+// The common module has to be declared in every test file that uses it.
+mod common;
+
+use common::golden::{self, assert_golden};
+use test_organisation::write_add_two_table;
+
+#[test]
+fn test_add_two_table() {
+    let output = common::capture(|out| write_add_two_table(out, &[-5, 0, 40, i32::MAX - 2, i32::MAX - 1, i32::MAX]));
+
+    assert_golden("add_two_table.txt", &output);
+}
+
+#[test]
+fn test_empty_add_two_table() {
+    let output = common::capture(|out| write_add_two_table(out, &[]));
+
+    assert_golden("empty_add_two_table.txt", &output);
+}
+
+// The golden helpers themselves. These never update anything, whatever
+// UPDATE_GOLDEN is set to.
+#[test]
+fn test_mismatch_reports_first_different_line() {
+    let path = golden::golden_path("empty_add_two_table.txt");
+    let error = golden::compare(&path, "           a |        a + 2\nsomething else\n", false).unwrap_err();
+
+    assert!(error.contains("from line 2"), "{}", error);
+    assert!(error.contains("something else"), "{}", error);
+    assert!(golden::compare(&path, &common::capture(|out| write_add_two_table(out, &[])), false).is_ok());
+}
+
+#[test]
+fn test_missing_golden_file() {
+    let error = golden::compare(&golden::golden_path("does_not_exist.txt"), "", false).unwrap_err();
+
+    assert!(error.contains("UPDATE_GOLDEN=1"), "{}", error);
+}
+
+#[test]
+fn test_trailing_newline_difference() {
+    let path = golden::golden_path("empty_add_two_table.txt");
+    let output = common::capture(|out| write_add_two_table(out, &[]));
+    let error = golden::compare(&path, output.trim_end(), false).unwrap_err();
+
+    assert!(error.contains("newlines at the end: expected 1, actual 0"), "{}", error);
+}
+
+#[test]
+fn test_update_only_for_truthy_values() {
+    assert!(golden::update_requested(Some("1")));
+    assert!(golden::update_requested(Some("yes")));
+    assert!(!golden::update_requested(None));
+    assert!(!golden::update_requested(Some("0")));
+    assert!(!golden::update_requested(Some("false")));
+    assert!(!golden::update_requested(Some("")));
+}
@@ -5,6 +5,8 @@
 pub struct Config {
     pub query: String,
     pub filepath: String,
+    pub other_filepaths: Vec<String>,
+    pub stats: Option<StatsFormat>,
 }
 
 // We can create a Config struct using a constructor as seen below.
@@ -27,12 +29,34 @@ use std::fs;
 /// This matches the definition of the Args iterator but still allows
 /// the developer to create their own Args-like object with wanting
 /// to test Config.
-
+///
+/// Any argument starting with -- is treated as a flag rather than a
+/// positional argument, so flags can go anywhere on the command line.
+/// A -- on its own ends the flags, for queries that start with --.
+/// Positional arguments after the first file path are extra files to search.
 impl Config {
-    pub fn new<I>(mut args: I) -> Result<Config, &'static str>
+    pub fn new<I>(args: I) -> Result<Config, &'static str>
     where I: Iterator<Item = String>
     {
-        let _program_path = args.next();
+        let mut stats = None;
+        let mut positional = Vec::new();
+        let mut args = args.skip(1);
+
+        while let Some(arg) = args.next() {
+            // Everything after -- is positional, so a query such as "--stats"
+            // can still be searched for with: -- --stats file.txt
+            if arg == "--" {
+                positional.extend(args.by_ref());
+                break;
+            }
+
+            match parse_flag(&arg) {
+                Some(flag) => stats = Some(flag?),
+                None => positional.push(arg),
+            }
+        }
+
+        let mut args = positional.into_iter();
 
         let query = match args.next() {
            Some(arg) => arg,
@@ -44,8 +68,31 @@ impl Config {
             None => return Err("Error, not enough arguments!"),
         };
 
-        Ok(Config { query, filepath })
+        let other_filepaths = args.collect();
+
+        Ok(Config { query, filepath, other_filepaths, stats })
+    }
+
+    /// Every file to search, in the order they were given.
+    pub fn filepaths(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.filepath).chain(self.other_filepaths.iter())
+    }
+}
+
+// Returns None if the argument isn't a flag at all.
+// --stats on its own means a human readable report.
+fn parse_flag(arg: &str) -> Option<Result<StatsFormat, &'static str>> {
+    if !arg.starts_with("--") {
+        return None;
     }
+
+    Some(match arg {
+        "--stats" => Ok(StatsFormat::Human),
+        _ => match arg.strip_prefix("--stats=") {
+            Some(format) => StatsFormat::parse(format),
+            None => Err("Error, unknown flag!"),
+        },
+    })
 }
 
 // To make the main function more manageable we will split off all logic that
@@ -54,14 +101,33 @@ impl Config {
 // verified by inspection.
 
 use std::error::Error;
+use std::time::Instant;
+
+mod stats;
+pub use stats::{FileStats, SearchStats, StatsFormat};
 
 pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    // Read in the contents of the file
-    let file_contents = fs::read_to_string(config.filepath)?;
-    
-    // Print out results
-    for line in search(&config.query, &file_contents) {
-        println!("{}", line);
+    let start = Instant::now();
+    let mut stats = SearchStats::new();
+
+    for filepath in config.filepaths() {
+        // Read in the contents of the file
+        let file_contents = fs::read_to_string(filepath)?;
+        let results = search(&config.query, &file_contents);
+
+        // Print out results
+        for line in &results {
+            println!("{}", line);
+        }
+
+        stats.record_file(filepath, file_contents.len(), results.len());
+    }
+
+    // The report comes after all the results so it doesn't get mixed up
+    // with the matching lines.
+    if let Some(format) = config.stats {
+        stats.elapsed = start.elapsed();
+        println!("{}", stats.render(format));
     }
 
     Ok(())
@@ -127,4 +193,61 @@ mod config_tests {
         
         let _config_result = Config::new(test_args).unwrap();
     }
+
+    #[test]
+    fn test_stats_flag_anywhere() {
+        let test_args = vec!["test", "--stats", "arg1", "arg2", "arg3"]
+            .into_iter()
+            .map(String::from);
+        let test_config = Config::new(test_args).unwrap();
+
+        assert_eq!(test_config.stats, Some(StatsFormat::Human));
+        assert_eq!(test_config.query, "arg1");
+        assert_eq!(
+            test_config.filepaths().collect::<Vec<_>>(),
+            vec!["arg2", "arg3"]
+        );
+    }
+
+    #[test]
+    fn test_stats_json_flag() {
+        let test_args = vec!["test", "arg1", "arg2", "--stats=json"]
+            .into_iter()
+            .map(String::from);
+        let test_config = Config::new(test_args).unwrap();
+
+        assert_eq!(test_config.stats, Some(StatsFormat::Json));
+    }
+
+    #[test]
+    fn test_error_on_bad_flags() {
+        for flag in ["--stats=xml", "--verbose"] {
+            let test_args = vec!["test", "arg1", "arg2", flag]
+                .into_iter()
+                .map(String::from);
+
+            assert!(Config::new(test_args).is_err());
+        }
+    }
+
+    #[test]
+    fn test_bad_flag_before_good_one() {
+        let test_args = vec!["test", "--bogus", "--stats", "arg1", "arg2"]
+            .into_iter()
+            .map(String::from);
+
+        assert_eq!(Config::new(test_args).err(), Some("Error, unknown flag!"));
+    }
+
+    #[test]
+    fn test_double_dash_ends_flags() {
+        let test_args = vec!["test", "--stats", "--", "--verbose", "arg2"]
+            .into_iter()
+            .map(String::from);
+        let test_config = Config::new(test_args).unwrap();
+
+        assert_eq!(test_config.stats, Some(StatsFormat::Human));
+        assert_eq!(test_config.query, "--verbose");
+        assert_eq!(test_config.filepath, "arg2");
+    }
 }
@@ -28,7 +28,6 @@ use minigrep::{Config, run};
 /// The only edit we've made to the main function is to remove the call to 
 /// collect on args to leave it as an iterator make is a direct pass rather 
 /// than a pass to a reference.
-
 fn main() {
     // Get string query and filename
     let arg_vec= env::args();
@@ -0,0 +1,204 @@
+// When the --stats flag is given, run keeps a tally of how much work it did
+// while searching and prints a report once all the matching lines are out.
+// This is mainly so slow searches can be spotted in CI logs, so the report
+// can be printed either for a human or as a single line of JSON.
+
+use std::time::Duration;
+
+/// The two ways a stats report can be printed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StatsFormat {
+    Human,
+    Json,
+}
+
+impl StatsFormat {
+    /// Parse the value of a --stats=<format> flag.
+    pub fn parse(value: &str) -> Result<StatsFormat, &'static str> {
+        match value {
+            "human" => Ok(StatsFormat::Human),
+            "json" => Ok(StatsFormat::Json),
+            _ => Err("Error, stats format must be human or json!"),
+        }
+    }
+}
+
+/// The tally for a single searched file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileStats {
+    pub filepath: String,
+    pub bytes: usize,
+    pub matches: usize,
+}
+
+/// The tally for a whole call to run.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct SearchStats {
+    pub files: Vec<FileStats>,
+    pub elapsed: Duration,
+}
+
+impl SearchStats {
+    pub fn new() -> SearchStats {
+        SearchStats::default()
+    }
+
+    pub fn record_file(&mut self, filepath: &str, bytes: usize, matches: usize) {
+        self.files.push(FileStats {
+            filepath: filepath.to_string(),
+            bytes,
+            matches,
+        });
+    }
+
+    pub fn files_searched(&self) -> usize {
+        self.files.len()
+    }
+
+    pub fn bytes_scanned(&self) -> usize {
+        self.files.iter().map(|f| f.bytes).sum()
+    }
+
+    pub fn lines_matched(&self) -> usize {
+        self.files.iter().map(|f| f.matches).sum()
+    }
+
+    /// Bytes scanned per second.
+    /// A search that finished too quickly to be timed reports zero rather
+    /// than dividing by zero.
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.elapsed.as_secs_f64();
+        if seconds == 0.0 {
+            return 0.0;
+        }
+        self.bytes_scanned() as f64 / seconds
+    }
+
+    pub fn render(&self, format: StatsFormat) -> String {
+        match format {
+            StatsFormat::Human => self.render_human(),
+            StatsFormat::Json => self.render_json(),
+        }
+    }
+
+    fn render_human(&self) -> String {
+        let mut report = String::from("--- stats ---\n");
+        report.push_str(&format!("Files searched: {}\n", self.files_searched()));
+        report.push_str(&format!("Bytes scanned:  {}\n", self.bytes_scanned()));
+        report.push_str(&format!("Lines matched:  {}\n", self.lines_matched()));
+        for file in &self.files {
+            report.push_str(&format!("  {}: {}\n", file.filepath, file.matches));
+        }
+        report.push_str(&format!(
+            "Elapsed:        {:.3} ms\n",
+            self.elapsed.as_secs_f64() * 1000.0
+        ));
+        report.push_str(&format!(
+            "Throughput:     {:.2} MB/s",
+            self.throughput() / 1_000_000.0
+        ));
+        report
+    }
+
+    // There are no dependencies in this crate so the JSON is written by hand.
+    // The only user supplied strings are the file paths, which are escaped.
+    fn render_json(&self) -> String {
+        let files: Vec<String> = self
+            .files
+            .iter()
+            .map(|f| {
+                format!(
+                    "{{\"file\":\"{}\",\"bytes\":{},\"matches\":{}}}",
+                    escape_json(&f.filepath),
+                    f.bytes,
+                    f.matches
+                )
+            })
+            .collect();
+
+        format!(
+            "{{\"files_searched\":{},\"bytes_scanned\":{},\"lines_matched\":{},\
+\"matches_per_file\":[{}],\"elapsed_ms\":{:.3},\"bytes_per_sec\":{:.0}}}",
+            self.files_searched(),
+            self.bytes_scanned(),
+            self.lines_matched(),
+            files.join(","),
+            self.elapsed.as_secs_f64() * 1000.0,
+            self.throughput(),
+        )
+    }
+}
+
+fn escape_json(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod stats_tests {
+    use super::*;
+
+    fn get_test_stats() -> SearchStats {
+        let mut stats = SearchStats::new();
+        stats.record_file("poem.txt", 1500, 3);
+        stats.record_file("other.txt", 500, 1);
+        stats.elapsed = Duration::from_millis(2);
+        stats
+    }
+
+    #[test]
+    fn test_totals() {
+        let stats = get_test_stats();
+
+        assert_eq!(stats.files_searched(), 2);
+        assert_eq!(stats.bytes_scanned(), 2000);
+        assert_eq!(stats.lines_matched(), 4);
+        assert_eq!(stats.throughput(), 1_000_000.0);
+    }
+
+    #[test]
+    fn test_zero_elapsed_throughput() {
+        let mut stats = get_test_stats();
+        stats.elapsed = Duration::from_secs(0);
+
+        assert_eq!(stats.throughput(), 0.0);
+    }
+
+    #[test]
+    fn test_render_json() {
+        let stats = get_test_stats();
+
+        assert_eq!(
+            stats.render(StatsFormat::Json),
+            "{\"files_searched\":2,\"bytes_scanned\":2000,\"lines_matched\":4,\
+\"matches_per_file\":[{\"file\":\"poem.txt\",\"bytes\":1500,\"matches\":3},\
+{\"file\":\"other.txt\",\"bytes\":500,\"matches\":1}],\
+\"elapsed_ms\":2.000,\"bytes_per_sec\":1000000}"
+        );
+    }
+
+    #[test]
+    fn test_render_human() {
+        let report = get_test_stats().render(StatsFormat::Human);
+
+        assert!(report.contains("Files searched: 2"));
+        assert!(report.contains("  poem.txt: 3"));
+        assert!(report.contains("Throughput:     1.00 MB/s"));
+    }
+
+    #[test]
+    fn test_escape_json() {
+        assert_eq!(escape_json("a\"b\\c\n"), "a\\\"b\\\\c\\n");
+    }
+}
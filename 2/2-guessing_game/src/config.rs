@@ -0,0 +1,170 @@
+// Command line options for the game, parsed in the same way as minigrep's
+// Config so that it can be tested with a hand-made iterator of arguments.
+
+use std::net::IpAddr;
+use std::path::PathBuf;
+
+use crate::variants::VariantKind;
+use crate::Difficulty;
+
+// Who is doing the guessing.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum Mode {
+    #[default]
+    Play,
+    Auto,
+    Reverse,
+    Serve(u16),
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Config {
+    pub mode: Mode,
+    pub difficulty: Option<Difficulty>,
+    pub seed: Option<u64>,
+    pub scores_path: Option<PathBuf>,
+    pub variant: Option<VariantKind>,
+    // Where --serve listens. Only this machine can connect unless another
+    // address is given.
+    pub address: Option<IpAddr>,
+}
+
+impl Config {
+    pub fn new<I>(args: I) -> Result<Config, &'static str>
+    where I: Iterator<Item = String>
+    {
+        let mut config = Config::default();
+        let mut args = args.skip(1);
+
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--difficulty" | "-d" => {
+                    let level = args.next().ok_or("No difficulty given after --difficulty!")?;
+                    let difficulty = Difficulty::parse(&level)
+                        .ok_or("Difficulty must be easy, medium or hard!")?;
+                    config.difficulty = Some(difficulty);
+                }
+                "--seed" => {
+                    let seed = args.next().ok_or("No seed given after --seed!")?;
+                    let seed = seed.parse().map_err(|_| "Seed must be a positive whole number!")?;
+                    config.seed = Some(seed);
+                }
+                "--scores" => {
+                    let path = args.next().ok_or("No file given after --scores!")?;
+                    config.scores_path = Some(PathBuf::from(path));
+                }
+                "--variant" => {
+                    let name = args.next().ok_or("No variant given after --variant!")?;
+                    let variant = VariantKind::parse(&name)
+                        .ok_or("Variant must be hotcold, bulls or float!")?;
+                    config.variant = Some(variant);
+                }
+                "--auto" => config.mode = Mode::Auto,
+                "--reverse" => config.mode = Mode::Reverse,
+                "--serve" => {
+                    let port = args.next().ok_or("No port given after --serve!")?;
+                    let port = port.parse().map_err(|_| "Port must be a number from 0 to 65535!")?;
+                    config.mode = Mode::Serve(port);
+                }
+                "--address" => {
+                    let address = args.next().ok_or("No address given after --address!")?;
+                    let address = address.parse().map_err(|_| "Address must be an IP address such as 0.0.0.0!")?;
+                    config.address = Some(address);
+                }
+                _ => return Err("Unknown argument!"),
+            }
+        }
+
+        // Only the game played at the terminal has variants.
+        if config.variant.is_some() && config.mode != Mode::Play {
+            return Err("--variant can't be used with --auto, --reverse or --serve!");
+        }
+
+        // Only the server listens on an address.
+        if config.address.is_some() && !matches!(config.mode, Mode::Serve(_)) {
+            return Err("--address can only be used with --serve!");
+        }
+
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod config_tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        let mut all = vec!["guessing_game".to_string()];
+        all.extend(args.iter().map(|a| a.to_string()));
+        all.into_iter()
+    }
+
+    #[test]
+    fn test_no_arguments() {
+        assert_eq!(Config::new(args(&[])).unwrap(), Config::default());
+    }
+
+    #[test]
+    fn test_difficulty_and_seed() {
+        let config = Config::new(args(&["--seed", "42", "-d", "hard"])).unwrap();
+
+        assert_eq!(config.difficulty, Some(Difficulty::Hard));
+        assert_eq!(config.seed, Some(42));
+    }
+
+    #[test]
+    fn test_modes() {
+        assert_eq!(Config::new(args(&["--auto"])).unwrap().mode, Mode::Auto);
+        assert_eq!(Config::new(args(&["--reverse"])).unwrap().mode, Mode::Reverse);
+        assert_eq!(Config::new(args(&["--serve", "7878"])).unwrap().mode, Mode::Serve(7878));
+        assert!(Config::new(args(&["--serve", "99999"])).is_err());
+    }
+
+    #[test]
+    fn test_address() {
+        let config = Config::new(args(&["--serve", "7878", "--address", "0.0.0.0"])).unwrap();
+
+        assert_eq!(config.address, Some(IpAddr::from([0, 0, 0, 0])));
+        assert!(Config::new(args(&["--serve", "7878", "--address", "localhost"])).is_err());
+    }
+
+    #[test]
+    fn test_address_only_when_serving() {
+        assert_eq!(
+            Config::new(args(&["--address", "0.0.0.0"])).err(),
+            Some("--address can only be used with --serve!")
+        );
+        assert!(Config::new(args(&["--auto", "--address", "127.0.0.1"])).is_err());
+    }
+
+    #[test]
+    fn test_variant() {
+        let config = Config::new(args(&["--variant", "bulls"])).unwrap();
+
+        assert_eq!(config.variant, Some(VariantKind::BullsAndCows));
+        assert!(Config::new(args(&["--variant", "chess"])).is_err());
+    }
+
+    #[test]
+    fn test_variant_only_when_playing() {
+        assert!(Config::new(args(&["--variant", "bulls", "--auto"])).is_err());
+        assert!(Config::new(args(&["--reverse", "--variant", "float"])).is_err());
+        assert!(Config::new(args(&["--variant", "hotcold", "--serve", "7878"])).is_err());
+    }
+
+    #[test]
+    fn test_scores_path() {
+        let config = Config::new(args(&["--scores", "my_scores.txt"])).unwrap();
+
+        assert_eq!(config.scores_path, Some(PathBuf::from("my_scores.txt")));
+    }
+
+    #[test]
+    fn test_bad_arguments() {
+        assert!(Config::new(args(&["--seed"])).is_err());
+        assert!(Config::new(args(&["--seed", "-1"])).is_err());
+        assert!(Config::new(args(&["--difficulty", "silly"])).is_err());
+        assert!(Config::new(args(&["--cheat"])).is_err());
+        assert!(Config::new(args(&["--scores"])).is_err());
+    }
+}
@@ -0,0 +1,72 @@
+// Each difficulty level sets both the range the secret number is picked from
+// and how many guesses the player gets before they lose.
+// The attempt limits are chosen so that a player who halves the range on
+// every guess can always win.
+
+use std::ops::RangeInclusive;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Difficulty {
+    Easy,
+    Medium,
+    Hard,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 3] = [Difficulty::Easy, Difficulty::Medium, Difficulty::Hard];
+
+    pub fn range(&self) -> RangeInclusive<u32> {
+        match self {
+            Difficulty::Easy => 1..=50,
+            Difficulty::Medium => 1..=100,
+            Difficulty::Hard => 1..=1000,
+        }
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        match self {
+            Difficulty::Easy => 10,
+            Difficulty::Medium => 7,
+            Difficulty::Hard => 10,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Medium => "medium",
+            Difficulty::Hard => "hard",
+        }
+    }
+
+    // Accepts either the name of the level or its number in the start menu.
+    pub fn parse(input: &str) -> Option<Difficulty> {
+        match input.trim().to_lowercase().as_str() {
+            "1" | "e" | "easy" => Some(Difficulty::Easy),
+            "2" | "m" | "medium" => Some(Difficulty::Medium),
+            "3" | "h" | "hard" => Some(Difficulty::Hard),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod difficulty_tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_names_and_numbers() {
+        assert_eq!(Difficulty::parse("Hard\n"), Some(Difficulty::Hard));
+        assert_eq!(Difficulty::parse("1"), Some(Difficulty::Easy));
+        assert_eq!(Difficulty::parse("impossible"), None);
+    }
+
+    #[test]
+    fn test_attempts_are_enough_to_bisect() {
+        for difficulty in Difficulty::ALL.iter() {
+            let size = difficulty.range().count() as f64;
+            let needed = (size + 1.0).log2().ceil() as u32;
+            assert!(difficulty.max_attempts() >= needed, "{:?}", difficulty);
+        }
+    }
+}
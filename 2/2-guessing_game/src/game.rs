@@ -0,0 +1,155 @@
+// The state of a single round of the guessing game.
+// Keeping this out of main means the rules can be tested without having to
+// type guesses into a terminal.
+
+use std::cmp::Ordering;
+use std::time::{Duration, Instant};
+
+use crate::Difficulty;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum GuessOutcome {
+    TooSmall,
+    TooBig,
+    OutOfRange,
+    Win,
+    Lose,
+}
+
+#[derive(Debug)]
+pub struct Game {
+    difficulty: Difficulty,
+    secret_number: u32,
+    attempts: u32,
+    won: bool,
+    started: Instant,
+    finished: Option<Duration>,
+}
+
+impl Game {
+    pub fn new(difficulty: Difficulty, secret_number: u32) -> Game {
+        Game {
+            difficulty,
+            secret_number,
+            attempts: 0,
+            won: false,
+            started: Instant::now(),
+            finished: None,
+        }
+    }
+
+    pub fn difficulty(&self) -> Difficulty {
+        self.difficulty
+    }
+
+    pub fn secret_number(&self) -> u32 {
+        self.secret_number
+    }
+
+    pub fn attempts(&self) -> u32 {
+        self.attempts
+    }
+
+    pub fn attempts_left(&self) -> u32 {
+        self.difficulty.max_attempts() - self.attempts
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.won || self.attempts_left() == 0
+    }
+
+    pub fn is_won(&self) -> bool {
+        self.won
+    }
+
+    // The clock starts when the game is created and stops on the guess that
+    // ends it, so time spent afterwards (e.g. typing a name) isn't counted.
+    pub fn elapsed(&self) -> Duration {
+        self.finished.unwrap_or_else(|| self.started.elapsed())
+    }
+
+    // Guesses outside of the difficulty's range don't use up an attempt.
+    // Once the game is over every further guess just repeats the result.
+    pub fn guess(&mut self, guess: u32) -> GuessOutcome {
+        if self.won {
+            return GuessOutcome::Win;
+        }
+        if self.attempts_left() == 0 {
+            return GuessOutcome::Lose;
+        }
+        if !self.difficulty.range().contains(&guess) {
+            return GuessOutcome::OutOfRange;
+        }
+
+        self.attempts += 1;
+
+        let outcome = match guess.cmp(&self.secret_number) {
+            Ordering::Equal => {
+                self.won = true;
+                GuessOutcome::Win
+            }
+            _ if self.attempts_left() == 0 => GuessOutcome::Lose,
+            Ordering::Less => GuessOutcome::TooSmall,
+            Ordering::Greater => GuessOutcome::TooBig,
+        };
+
+        if self.is_over() {
+            self.finished = Some(self.started.elapsed());
+        }
+
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod game_tests {
+    use super::*;
+
+    #[test]
+    fn test_feedback_and_win() {
+        let mut game = Game::new(Difficulty::Medium, 42);
+
+        assert_eq!(game.guess(10), GuessOutcome::TooSmall);
+        assert_eq!(game.guess(90), GuessOutcome::TooBig);
+        assert_eq!(game.guess(42), GuessOutcome::Win);
+        assert_eq!(game.attempts(), 3);
+        assert!(game.is_over());
+        assert!(game.is_won());
+
+        // The clock has stopped now the game is won.
+        assert_eq!(game.elapsed(), game.elapsed());
+    }
+
+    #[test]
+    fn test_out_of_range_is_free() {
+        let mut game = Game::new(Difficulty::Easy, 7);
+
+        assert_eq!(game.guess(0), GuessOutcome::OutOfRange);
+        assert_eq!(game.guess(51), GuessOutcome::OutOfRange);
+        assert_eq!(game.attempts_left(), 10);
+    }
+
+    #[test]
+    fn test_lose_when_out_of_attempts() {
+        let mut game = Game::new(Difficulty::Medium, 42);
+
+        for _ in 0..6 {
+            assert_eq!(game.guess(1), GuessOutcome::TooSmall);
+        }
+        assert_eq!(game.guess(1), GuessOutcome::Lose);
+        assert!(game.is_over());
+
+        // The game is finished so even the right answer doesn't win now.
+        assert_eq!(game.guess(42), GuessOutcome::Lose);
+    }
+
+    #[test]
+    fn test_win_on_last_attempt() {
+        let mut game = Game::new(Difficulty::Medium, 42);
+
+        for _ in 0..6 {
+            game.guess(1);
+        }
+        assert_eq!(game.guess(42), GuessOutcome::Win);
+    }
+}
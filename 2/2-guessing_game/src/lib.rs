@@ -0,0 +1,20 @@
+// The rules of the guessing game live in this library so that they can be
+// tested. main.rs is left to deal with the real terminal and random numbers.
+
+mod config;
+mod difficulty;
+mod game;
+mod play;
+mod scores;
+mod server;
+mod solver;
+pub mod variants;
+
+pub use config::{Config, Mode};
+pub use difficulty::Difficulty;
+pub use game::{Game, GuessOutcome};
+pub use play::play;
+pub use scores::{record_win, Score, ScoreTable, DEFAULT_SCORES_PATH};
+pub use server::serve;
+pub use solver::{auto_play, reverse_play, Bisector};
+pub use variants::{play_variant, Variant, VariantKind};
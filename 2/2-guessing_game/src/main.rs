@@ -2,51 +2,94 @@ use std::io;                                    // This is how stdio is imported
                                                 // It is not needed for the println! macro
                                                 // We have imported the io *thing* from std
 
+use std::env;
+use std::net::{Ipv4Addr, TcpListener};
+use std::path::Path;
+use std::process;
 
-use rand::Rng;                                  // Random number generator
+use rand::rngs::StdRng;                         // Random number generator
+use rand::SeedableRng;                          // A seeded StdRng always picks the same numbers
 
-use std::cmp::Ordering;                         // Enum: Less Greater, Equal
-                                                // This is put out by the String.cmp() method
-                                                // The result of the String.cmp() can be detected with a match statement                           
+use guessing_game::{
+    auto_play, play, play_variant, record_win, reverse_play, serve, Config, Difficulty, Mode,
+    DEFAULT_SCORES_PATH,
+};
 
 fn main() {
-    println!("Guess the number!");
-
-    let secret_number = rand::thread_rng().gen_range(1..=100);
-    
-    let mut guess = String::new();              // let creates variable, mut specifies that it is mutable
-                                                // String is an extendable, UTF-8 encoded string
-                                                // new is an associate function, working on the type, rather than an object (static function)
-
-    loop {
-    
-        println!("Please input your guess below:");     
-
-        guess.clear();                              // Empty string of current guess
-
-        io::stdin()                                 // The stdin() function returns an instance of std::io::Stdin which is a handle for io
-            .read_line(&mut guess)                  // Call the read line method, appends terminal line to given string. & is reference, mut is given to make the reference mutable
-            .expect("Failed to read line");         // read_line() returns an io::Result object.
-                                                    // If this object is contains an err object, crash the program and print the given strin
-                                                   
-        
-        let guess: u32 = match guess.trim().parse() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Could not parse number!");
-                continue;
-            },
-        };
-
-        println!("You guessed {}", guess);
-
-        match guess.cmp(&secret_number) {
-            Ordering::Less => println!("Too small!"),
-            Ordering::Greater => println!("Too big!"),
-            Ordering::Equal => {
-                println!("You win!");
-                break;
-            },
+    let config = Config::new(env::args()).unwrap_or_else(|err| {
+        println!("Problem parsing arguments: {}", err);
+        process::exit(1);
+    });
+
+    // The game takes any Rng so --seed can make it pick the same secret
+    // number every time. Without a seed it is seeded from the OS.
+    let mut rng = match config.seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+
+    // The stdin() function returns an instance of std::io::Stdin which is a handle for io
+    // Locking it gives a handle that implements BufRead, which the game reads guesses from
+    let stdin = io::stdin();
+    let mut input = stdin.lock();
+    let mut output = io::stdout();
+
+    // The variants have their own rules so they don't go on the high score
+    // table, which is for the classic game.
+    if let (Mode::Play, Some(variant)) = (config.mode, config.variant) {
+        let result = play_variant(variant, config.difficulty, &mut rng, &mut input, &mut output);
+        if let Err(e) = result {
+            println!("Application Error: {}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    let result = match config.mode {
+        Mode::Play => play(config.difficulty, &mut rng, &mut input, &mut output),
+        Mode::Auto => {
+            let difficulty = config.difficulty.unwrap_or(Difficulty::Medium);
+            auto_play(difficulty, &mut rng, &mut output)
+        }
+        Mode::Reverse => {
+            // The player picked the number so there's no score to record.
+            if let Err(e) = reverse_play(config.difficulty, &mut input, &mut output) {
+                println!("Application Error: {}", e);
+                process::exit(1);
+            }
+            return;
+        }
+        Mode::Serve(port) => {
+            let difficulty = config.difficulty.unwrap_or(Difficulty::Medium);
+            let address = config.address.unwrap_or(Ipv4Addr::LOCALHOST.into());
+            let result = TcpListener::bind((address, port)).and_then(|listener| {
+                println!("Serving the guessing game on {}", listener.local_addr()?);
+                serve(listener, difficulty, rng)
+            });
+            if let Err(e) = result {
+                println!("Application Error: {}", e);
+                process::exit(1);
+            }
+            return;
+        }
+    };
+
+    let game = result.unwrap_or_else(|e| {
+        println!("Application Error: {}", e);
+        process::exit(1);
+    });
+
+    // Only winners make it onto the high score table, and the computer
+    // doesn't get to put its name down.
+    if game.is_won() && config.mode == Mode::Play {
+        let scores_path = config
+            .scores_path
+            .as_deref()
+            .unwrap_or_else(|| Path::new(DEFAULT_SCORES_PATH));
+
+        if let Err(e) = record_win(scores_path, &game, &mut input, &mut output) {
+            println!("Application Error: {}", e);
+            process::exit(1);
         }
     }
-}
\ No newline at end of file
+}
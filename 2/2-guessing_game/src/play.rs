@@ -0,0 +1,115 @@
+// The game loop that used to be in main.
+// Rather than using thread_rng, stdin and stdout directly it takes them as
+// arguments, so a test can hand it a seeded RNG, a script of guesses and a
+// buffer to collect everything that gets printed.
+
+use std::io::{self, BufRead, Write};
+
+use rand::Rng;
+
+use crate::{Difficulty, Game, GuessOutcome};
+
+pub fn play<R, I, O>(
+    difficulty: Option<Difficulty>,
+    rng: &mut R,
+    input: &mut I,
+    output: &mut O,
+) -> io::Result<Game>
+where
+    R: Rng,
+    I: BufRead,
+    O: Write,
+{
+    writeln!(output, "Guess the number!")?;
+
+    let difficulty = match difficulty {
+        Some(difficulty) => difficulty,
+        None => choose_difficulty(input, output)?,      // No --difficulty given so ask for one
+    };
+
+    let secret_number = rng.gen_range(difficulty.range());
+    let mut game = Game::new(difficulty, secret_number);
+
+    let range = difficulty.range();
+    writeln!(
+        output,
+        "Playing on {}: the number is between {} and {} and you have {} attempts.",
+        difficulty.name(),
+        range.start(),
+        range.end(),
+        difficulty.max_attempts(),
+    )?;
+
+    while !game.is_over() {
+        writeln!(output, "Please input your guess below ({} attempts left):", game.attempts_left())?;
+
+        let guess: u32 = match read_line(input)?.trim().parse() {
+            Ok(num) => num,
+            Err(_) => {
+                writeln!(output, "Could not parse number!")?;
+                continue;
+            }
+        };
+
+        writeln!(output, "You guessed {}", guess)?;
+
+        match game.guess(guess) {
+            GuessOutcome::TooSmall => writeln!(output, "Too small!")?,
+            GuessOutcome::TooBig => writeln!(output, "Too big!")?,
+            GuessOutcome::OutOfRange => writeln!(
+                output,
+                "That's not between {} and {}!",
+                range.start(),
+                range.end(),
+            )?,
+            GuessOutcome::Win => writeln!(output, "You win!")?,
+            GuessOutcome::Lose => writeln!(
+                output,
+                "Out of attempts, you lose! The number was {}.",
+                game.secret_number(),
+            )?,
+        }
+    }
+
+    Ok(game)
+}
+
+// Keep asking until one of the menu options is picked.
+pub(crate) fn choose_difficulty<I: BufRead, O: Write>(input: &mut I, output: &mut O) -> io::Result<Difficulty> {
+    loop {
+        writeln!(output, "Choose a difficulty:")?;
+        for (i, difficulty) in Difficulty::ALL.iter().enumerate() {
+            let range = difficulty.range();
+            writeln!(
+                output,
+                "  {}) {} ({}-{}, {} attempts)",
+                i + 1,
+                difficulty.name(),
+                range.start(),
+                range.end(),
+                difficulty.max_attempts(),
+            )?;
+        }
+
+        match Difficulty::parse(&read_line(input)?) {
+            Some(difficulty) => return Ok(difficulty),
+            None => writeln!(output, "That's not one of the options!")?,
+        }
+    }
+}
+
+// read_line returns Ok(0) at the end of the input rather than an error.
+// Without checking for that the game would keep asking for guesses forever
+// once a script of guesses (or a piped stdin) runs out.
+pub(crate) fn read_line<I: BufRead>(input: &mut I) -> io::Result<String> {
+    let mut line = String::new();
+
+    if input.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "Input ended before the game finished",
+        ));
+    }
+
+    Ok(line)
+}
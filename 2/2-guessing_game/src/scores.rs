@@ -0,0 +1,310 @@
+// The high score table, kept in a plain text file between games.
+// Each line of the file holds one score as tab separated fields:
+//
+//     difficulty  guesses  milliseconds  name
+//
+// The file is only ever written by this module but it lives on the user's
+// disk, so loading it has to cope with it being missing, edited by hand or
+// just plain broken. Lines that can't be understood are skipped and reported
+// rather than throwing the whole table away.
+
+use std::fs;
+use std::io::{self, BufRead, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::play::read_line;
+use crate::{Difficulty, Game};
+
+pub const DEFAULT_SCORES_PATH: &str = "guessing_game_scores.txt";
+
+const LEADERBOARD_SIZE: usize = 10;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Score {
+    pub name: String,
+    pub difficulty: Difficulty,
+    pub guesses: u32,
+    pub elapsed: Duration,
+}
+
+impl Score {
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}",
+            self.difficulty.name(),
+            self.guesses,
+            self.elapsed.as_millis(),
+            self.name,
+        )
+    }
+
+    fn from_line(line: &str) -> Result<Score, &'static str> {
+        let fields: Vec<&str> = line.splitn(4, '\t').collect();
+        if fields.len() != 4 {
+            return Err("expected 4 fields");
+        }
+
+        let difficulty = Difficulty::parse(fields[0]).ok_or("unknown difficulty")?;
+        let guesses = fields[1].parse().map_err(|_| "bad number of guesses")?;
+        let millis = fields[2].parse().map_err(|_| "bad time")?;
+        let name = clean_name(fields[3]);
+
+        Ok(Score {
+            name,
+            difficulty,
+            guesses,
+            elapsed: Duration::from_millis(millis),
+        })
+    }
+}
+
+// Names can't contain the tabs and newlines used to separate the file.
+fn clean_name(name: &str) -> String {
+    let name: String = name
+        .trim()
+        .chars()
+        .map(|c| if c.is_control() { ' ' } else { c })
+        .collect();
+
+    if name.is_empty() {
+        String::from("anonymous")
+    } else {
+        name
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct ScoreTable {
+    scores: Vec<Score>,
+}
+
+impl ScoreTable {
+    pub fn new() -> ScoreTable {
+        ScoreTable::default()
+    }
+
+    /// Parse the contents of a scores file.
+    /// Returns the table along with a warning for each line that was skipped.
+    pub fn parse(contents: &str) -> (ScoreTable, Vec<String>) {
+        let mut table = ScoreTable::new();
+        let mut warnings = Vec::new();
+
+        for (number, line) in contents.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match Score::from_line(line) {
+                Ok(score) => table.scores.push(score),
+                Err(reason) => warnings.push(format!(
+                    "Skipped line {} of the scores file: {}",
+                    number + 1,
+                    reason,
+                )),
+            }
+        }
+
+        (table, warnings)
+    }
+
+    /// Load the table from disk.
+    /// A missing file is just an empty table. A file that isn't text at all
+    /// is moved aside to <path>.corrupt so saving over it doesn't destroy
+    /// whatever was in it.
+    /// Any other error, such as not being allowed to read the file, might
+    /// not be a problem with the file itself so it is returned and the file
+    /// is left alone.
+    pub fn load(path: &Path) -> io::Result<(ScoreTable, Vec<String>)> {
+        match fs::read_to_string(path) {
+            Ok(contents) => Ok(ScoreTable::parse(&contents)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok((ScoreTable::new(), Vec::new())),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                let mut warning = format!("Could not read the scores file ({}), starting a new one", e);
+                let backup = corrupt_path(path);
+                if fs::rename(path, &backup).is_ok() {
+                    warning.push_str(&format!("; the old file was moved to {}", backup.display()));
+                }
+                Ok((ScoreTable::new(), vec![warning]))
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut contents = String::new();
+        for score in &self.scores {
+            contents.push_str(&score.to_line());
+            contents.push('\n');
+        }
+
+        // Write to a temporary file first so a crash part way through
+        // doesn't leave a half written table behind.
+        let temp_path = path.with_extension("tmp");
+        fs::write(&temp_path, contents)?;
+        fs::rename(&temp_path, path)
+    }
+
+    pub fn add(&mut self, score: Score) {
+        self.scores.push(score);
+    }
+
+    /// The best scores for a difficulty, fewest guesses first with the
+    /// quickest time breaking ties.
+    pub fn top(&self, difficulty: Difficulty) -> Vec<&Score> {
+        let mut scores: Vec<&Score> = self
+            .scores
+            .iter()
+            .filter(|s| s.difficulty == difficulty)
+            .collect();
+
+        scores.sort_by_key(|s| (s.guesses, s.elapsed));
+        scores.truncate(LEADERBOARD_SIZE);
+        scores
+    }
+
+    pub fn render_leaderboard(&self, difficulty: Difficulty) -> String {
+        let mut board = format!("High scores ({}):\n", difficulty.name());
+
+        for (rank, score) in self.top(difficulty).iter().enumerate() {
+            board.push_str(&format!(
+                "{:>3}. {:<20} {:>3} guesses {:>8.1}s\n",
+                rank + 1,
+                score.name,
+                score.guesses,
+                score.elapsed.as_secs_f64(),
+            ));
+        }
+
+        board
+    }
+}
+
+fn corrupt_path(path: &Path) -> PathBuf {
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".corrupt");
+    PathBuf::from(backup)
+}
+
+/// Ask the winner for their name, add their score to the table on disk and
+/// show the leaderboard for the difficulty they played.
+/// Problems with the scores file are reported but never stop the game.
+pub fn record_win<I, O>(path: &Path, game: &Game, input: &mut I, output: &mut O) -> io::Result<()>
+where
+    I: BufRead,
+    O: Write,
+{
+    writeln!(output, "Enter your name for the high score table:")?;
+    let name = match read_line(input) {
+        Ok(line) => clean_name(&line),
+        // Input that runs out after the winning guess, such as a piped
+        // script of guesses, just means there is no name to record.
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    // Saving a new table over a file that couldn't be read would lose the
+    // scores in it, so this score is dropped instead.
+    let (mut table, warnings) = match ScoreTable::load(path) {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            return writeln!(
+                output,
+                "Warning: could not read the scores file ({}), your score was not saved",
+                e,
+            );
+        }
+    };
+    for warning in warnings {
+        writeln!(output, "Warning: {}", warning)?;
+    }
+
+    table.add(Score {
+        name,
+        difficulty: game.difficulty(),
+        guesses: game.attempts(),
+        elapsed: game.elapsed(),
+    });
+
+    if let Err(e) = table.save(path) {
+        writeln!(output, "Warning: could not save the scores file: {}", e)?;
+    }
+
+    write!(output, "{}", table.render_leaderboard(game.difficulty()))
+}
+
+#[cfg(test)]
+mod scores_tests {
+    use super::*;
+
+    fn score(name: &str, difficulty: Difficulty, guesses: u32, millis: u64) -> Score {
+        Score {
+            name: name.to_string(),
+            difficulty,
+            guesses,
+            elapsed: Duration::from_millis(millis),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut table = ScoreTable::new();
+        table.add(score("Ferris", Difficulty::Hard, 9, 12345));
+        table.add(score("Bill Bob", Difficulty::Easy, 3, 500));
+
+        let contents: String = table.scores.iter().map(|s| s.to_line() + "\n").collect();
+        let (parsed, warnings) = ScoreTable::parse(&contents);
+
+        assert_eq!(parsed, table);
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn test_corrupt_lines_are_skipped() {
+        let contents = "easy\t3\t500\tFerris\n\
+                        garbage\n\
+                        medium\tlots\t500\tBill\n\
+                        \n\
+                        hard\t9\t100\tName\twith\ttabs\n";
+
+        let (table, warnings) = ScoreTable::parse(contents);
+
+        assert_eq!(table.scores.len(), 2);
+        assert_eq!(table.scores[1].name, "Name with tabs");
+        assert_eq!(warnings.len(), 2);
+        assert!(warnings[0].contains("line 2"));
+        assert!(warnings[1].contains("line 3"));
+    }
+
+    #[test]
+    fn test_top_sorts_and_filters() {
+        let mut table = ScoreTable::new();
+        table.add(score("slow", Difficulty::Medium, 4, 9000));
+        table.add(score("fast", Difficulty::Medium, 4, 1000));
+        table.add(score("best", Difficulty::Medium, 2, 5000));
+        table.add(score("other", Difficulty::Easy, 1, 10));
+
+        let names: Vec<&str> = table
+            .top(Difficulty::Medium)
+            .iter()
+            .map(|s| s.name.as_str())
+            .collect();
+
+        assert_eq!(names, vec!["best", "fast", "slow"]);
+    }
+
+    #[test]
+    fn test_top_is_limited_to_ten() {
+        let mut table = ScoreTable::new();
+        for i in 0..15 {
+            table.add(score("player", Difficulty::Hard, 10 - i % 10, 1000));
+        }
+
+        assert_eq!(table.top(Difficulty::Hard).len(), 10);
+    }
+
+    #[test]
+    fn test_empty_name_is_anonymous() {
+        assert_eq!(clean_name("  \n"), "anonymous");
+    }
+}
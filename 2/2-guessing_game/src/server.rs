@@ -0,0 +1,180 @@
+// A multiplayer version of the game played over TCP with --serve PORT.
+// Everyone who connects (e.g. with `nc localhost PORT`) races to guess the
+// same secret number. Each player only sees the feedback for their own
+// guesses, but when someone wins the whole server is told and a new round
+// starts straight away with a new number.
+// The server only listens on localhost unless it is given another address
+// with --address, e.g. --address 0.0.0.0 to let other machines join.
+//
+// Each connection gets its own thread. The secret number, the RNG and the
+// list of connected players are shared between them behind a Mutex.
+// Each player also has a writer thread that is the only thing writing to
+// their socket. Everything sent to a player goes through a channel to it, so
+// lines from different threads can't be mixed up, and a player who stops
+// reading only holds up their own writer rather than everyone's guesses.
+
+use std::cmp::Ordering;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::Difficulty;
+
+// How long a write to a player can take before they are given up on.
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+
+struct Player {
+    id: usize,
+    sender: Sender<String>,
+}
+
+struct Round {
+    difficulty: Difficulty,
+    rng: StdRng,
+    number: u32,
+    secret_number: u32,
+    players: Vec<Player>,
+}
+
+impl Round {
+    fn range_message(&self) -> String {
+        let range = self.difficulty.range();
+        format!(
+            "Round {}: guess the number between {} and {}.",
+            self.number,
+            range.start(),
+            range.end(),
+        )
+    }
+
+    // Sending only queues the message for each writer thread, so it is quick
+    // enough to do with the lock held. Doing it with the lock held means every
+    // player gets the announcements in the same order.
+    // A player who has disconnected can't be sent to, but they are removed
+    // by their own thread so the failure is ignored here.
+    fn broadcast(&self, message: &str) {
+        for player in &self.players {
+            let _ = player.sender.send(message.to_string());
+        }
+    }
+
+    fn next_round(&mut self) {
+        self.number += 1;
+        self.secret_number = self.rng.gen_range(self.difficulty.range());
+        let message = self.range_message();
+        self.broadcast(&message);
+    }
+}
+
+// Start the thread that writes everything sent to a player. It stops once
+// every Sender for it has been dropped. If a write fails or times out the
+// connection is shut down, and the player's own thread then sees it end and
+// takes them out of the round.
+fn spawn_writer(stream: TcpStream) -> io::Result<Sender<String>> {
+    stream.set_write_timeout(Some(WRITE_TIMEOUT))?;
+    let (sender, receiver) = mpsc::channel::<String>();
+
+    thread::spawn(move || {
+        for message in receiver {
+            if writeln!(&stream, "{}", message).is_err() {
+                let _ = stream.shutdown(Shutdown::Both);
+                break;
+            }
+        }
+    });
+
+    Ok(sender)
+}
+
+/// Run the server on an already bound listener until accepting fails.
+/// Taking the listener rather than a port lets tests bind to port 0 and
+/// find out which port they were given.
+pub fn serve(listener: TcpListener, difficulty: Difficulty, mut rng: StdRng) -> io::Result<()> {
+    let secret_number = rng.gen_range(difficulty.range());
+    let round = Arc::new(Mutex::new(Round {
+        difficulty,
+        rng,
+        number: 1,
+        secret_number,
+        players: Vec::new(),
+    }));
+
+    for (id, stream) in listener.incoming().enumerate() {
+        let stream = stream?;
+        let round = Arc::clone(&round);
+
+        thread::spawn(move || {
+            // An error here only affects this one player, so it is dropped
+            // along with their connection.
+            let _ = handle_player(id + 1, stream, round);
+        });
+    }
+
+    Ok(())
+}
+
+// However the connection ends the player is taken out of the round so
+// nothing more is broadcast to them.
+fn handle_player(id: usize, stream: TcpStream, round: Arc<Mutex<Round>>) -> io::Result<()> {
+    let result = play_rounds(id, stream, &round);
+    round.lock().unwrap().players.retain(|p| p.id != id);
+    result
+}
+
+// Replies go through the same channel as broadcasts so they stay in order
+// with them. A failed send means the writer has given up on the connection,
+// which the reader will find out about on its next read.
+fn play_rounds(id: usize, stream: TcpStream, round: &Mutex<Round>) -> io::Result<()> {
+    let reader = BufReader::new(stream.try_clone()?);
+    let sender = spawn_writer(stream)?;
+    let reply = |message: String| {
+        let _ = sender.send(message);
+    };
+
+    {
+        let mut round = round.lock().unwrap();
+        reply(format!("Welcome Player {}! {}", id, round.range_message()));
+        round.players.push(Player { id, sender: sender.clone() });
+    }
+
+    for line in reader.lines() {
+        let line = line?;
+
+        let guess: u32 = match line.trim().parse() {
+            Ok(num) => num,
+            Err(_) => {
+                reply(String::from("Could not parse number!"));
+                continue;
+            }
+        };
+
+        let mut round = round.lock().unwrap();
+        let range = round.difficulty.range();
+
+        if !range.contains(&guess) {
+            reply(format!("That's not between {} and {}!", range.start(), range.end()));
+            continue;
+        }
+
+        match guess.cmp(&round.secret_number) {
+            Ordering::Less => reply(String::from("Too small!")),
+            Ordering::Greater => reply(String::from("Too big!")),
+            Ordering::Equal => {
+                let message = format!(
+                    "Player {} guessed {} and wins round {}!",
+                    id, guess, round.number,
+                );
+                round.broadcast(&message);
+                round.next_round();
+            }
+        }
+    }
+
+    Ok(())
+}
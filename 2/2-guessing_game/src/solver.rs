@@ -0,0 +1,226 @@
+// The computer can play the game too.
+// Every Too small!/Too big! answer rules out one side of the last guess, so
+// guessing the middle of whatever is left halves the possibilities each time.
+// This is a binary search over the range, and it needs at most
+// log2(range size + 1) guesses, which is what each difficulty allows.
+//
+// The same strategy is used for both the --auto mode, where the computer
+// plays against its own secret number, and the --reverse mode, where the
+// player thinks of the number and answers the computer's guesses.
+
+use std::cmp::Ordering;
+use std::io::{self, BufRead, Write};
+use std::ops::RangeInclusive;
+
+use rand::Rng;
+
+use crate::play::{choose_difficulty, read_line};
+use crate::{Difficulty, Game, GuessOutcome};
+
+/// Keeps track of the numbers that could still be the secret.
+/// The bounds are stored as i64 so moving them past either end of a u32
+/// range can't overflow.
+#[derive(Debug)]
+pub struct Bisector {
+    low: i64,
+    high: i64,
+}
+
+impl Bisector {
+    pub fn new(range: RangeInclusive<u32>) -> Bisector {
+        Bisector {
+            low: *range.start() as i64,
+            high: *range.end() as i64,
+        }
+    }
+
+    /// The middle of the remaining range, or None if the feedback so far
+    /// has ruled out every number.
+    pub fn next_guess(&self) -> Option<u32> {
+        if self.is_inconsistent() {
+            return None;
+        }
+        Some((self.low + (self.high - self.low) / 2) as u32)
+    }
+
+    /// Narrow the range given how a guess compared to the secret number.
+    /// Ordering::Less means the guess was too small.
+    pub fn feedback(&mut self, guess: u32, ordering: Ordering) {
+        let guess = guess as i64;
+        match ordering {
+            Ordering::Less => self.low = self.low.max(guess + 1),
+            Ordering::Greater => self.high = self.high.min(guess - 1),
+            Ordering::Equal => {
+                // Only the guess itself is left, as long as it was possible.
+                if guess < self.low || guess > self.high {
+                    self.low = 1;
+                    self.high = 0;
+                } else {
+                    self.low = guess;
+                    self.high = guess;
+                }
+            }
+        }
+    }
+
+    pub fn is_inconsistent(&self) -> bool {
+        self.low > self.high
+    }
+}
+
+/// The computer picks a secret number and then bisects its way to it.
+pub fn auto_play<R, O>(difficulty: Difficulty, rng: &mut R, output: &mut O) -> io::Result<Game>
+where
+    R: Rng,
+    O: Write,
+{
+    let secret_number = rng.gen_range(difficulty.range());
+    let mut game = Game::new(difficulty, secret_number);
+    let mut bisector = Bisector::new(difficulty.range());
+
+    writeln!(output, "The computer is playing on {}.", difficulty.name())?;
+
+    while !game.is_over() {
+        let guess = match bisector.next_guess() {
+            Some(guess) => guess,
+            None => break,
+        };
+
+        writeln!(output, "Computer guessed {}", guess)?;
+
+        match game.guess(guess) {
+            GuessOutcome::TooSmall => {
+                writeln!(output, "Too small!")?;
+                bisector.feedback(guess, Ordering::Less);
+            }
+            GuessOutcome::TooBig => {
+                writeln!(output, "Too big!")?;
+                bisector.feedback(guess, Ordering::Greater);
+            }
+            GuessOutcome::OutOfRange => break,
+            GuessOutcome::Win => {
+                writeln!(output, "The computer wins in {} guesses!", game.attempts())?
+            }
+            GuessOutcome::Lose => writeln!(
+                output,
+                "The computer ran out of attempts! The number was {}.",
+                game.secret_number(),
+            )?,
+        }
+    }
+
+    Ok(game)
+}
+
+/// The player thinks of a number and the computer guesses it.
+/// Returns the number the computer settled on, or None if the player's
+/// answers contradicted each other.
+pub fn reverse_play<I, O>(difficulty: Option<Difficulty>, input: &mut I, output: &mut O) -> io::Result<Option<u32>>
+where
+    I: BufRead,
+    O: Write,
+{
+    let difficulty = match difficulty {
+        Some(difficulty) => difficulty,
+        None => choose_difficulty(input, output)?,
+    };
+
+    let range = difficulty.range();
+    writeln!(
+        output,
+        "Think of a number between {} and {} and I'll guess it.",
+        range.start(),
+        range.end(),
+    )?;
+
+    let mut bisector = Bisector::new(range);
+    let mut guesses = 0;
+
+    while let Some(guess) = bisector.next_guess() {
+        guesses += 1;
+        writeln!(output, "Is it {}? (s = too small, b = too big, c = correct)", guess)?;
+
+        let ordering = match parse_answer(&read_line(input)?) {
+            Some(ordering) => ordering,
+            None => {
+                writeln!(output, "Please answer s, b or c.")?;
+                guesses -= 1;
+                continue;
+            }
+        };
+
+        if ordering == Ordering::Equal {
+            writeln!(output, "Got it in {} guesses!", guesses)?;
+            return Ok(Some(guess));
+        }
+
+        bisector.feedback(guess, ordering);
+    }
+
+    writeln!(output, "Your answers don't add up, no number fits all of them!")?;
+    Ok(None)
+}
+
+// The answer says how the computer's guess compared to the player's number.
+fn parse_answer(answer: &str) -> Option<Ordering> {
+    match answer.trim().to_lowercase().as_str() {
+        "s" | "small" | "too small" | "<" => Some(Ordering::Less),
+        "b" | "big" | "too big" | ">" => Some(Ordering::Greater),
+        "c" | "correct" | "=" => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod solver_tests {
+    use super::*;
+
+    #[test]
+    fn test_bisector_finds_every_number_in_time() {
+        for difficulty in Difficulty::ALL.iter() {
+            for secret in difficulty.range() {
+                let mut game = Game::new(*difficulty, secret);
+                let mut bisector = Bisector::new(difficulty.range());
+
+                while !game.is_over() {
+                    let guess = bisector.next_guess().unwrap();
+                    game.guess(guess);
+                    bisector.feedback(guess, guess.cmp(&secret));
+                }
+
+                assert!(game.is_won(), "{:?} lost with secret {}", difficulty, secret);
+            }
+        }
+    }
+
+    #[test]
+    fn test_bisector_detects_contradiction() {
+        let mut bisector = Bisector::new(1..=100);
+
+        bisector.feedback(50, Ordering::Less);
+        bisector.feedback(51, Ordering::Greater);
+
+        assert!(bisector.is_inconsistent());
+        assert_eq!(bisector.next_guess(), None);
+    }
+
+    #[test]
+    fn test_bisector_edges_of_range() {
+        let mut bisector = Bisector::new(1..=1);
+
+        bisector.feedback(1, Ordering::Greater);
+        assert!(bisector.is_inconsistent());
+
+        let mut bisector = Bisector::new(1..=50);
+        bisector.feedback(60, Ordering::Equal);
+        assert!(bisector.is_inconsistent());
+    }
+
+    #[test]
+    fn test_parse_answer() {
+        assert_eq!(parse_answer("S\n"), Some(Ordering::Less));
+        assert_eq!(parse_answer(">"), Some(Ordering::Greater));
+        assert_eq!(parse_answer("correct"), Some(Ordering::Equal));
+        assert_eq!(parse_answer("maybe"), None);
+    }
+}
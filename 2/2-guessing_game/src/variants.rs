@@ -0,0 +1,131 @@
+// The classic game only ever says Too small!, Too big! or You win!.
+// The variants in this module change what the secret is and what feedback a
+// guess gets back. They all share the Variant trait, so play_variant can run
+// any of them with the same loop of reading a guess, showing the feedback and
+// counting attempts.
+//
+// To add a new variant: write a type that implements Variant in a file in
+// src/variants, then add it to VariantKind so it can be picked with
+// --variant NAME.
+
+use std::io::{self, BufRead, Write};
+
+use rand::Rng;
+
+use crate::play::{choose_difficulty, read_line};
+use crate::Difficulty;
+
+pub mod bulls_and_cows;
+pub mod float_range;
+pub mod hot_cold;
+
+use bulls_and_cows::BullsAndCows;
+use float_range::FloatRange;
+use hot_cold::HotCold;
+
+/// The result of a guess that could be understood.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Feedback {
+    pub message: String,
+    pub solved: bool,
+}
+
+impl Feedback {
+    pub fn hint(message: impl Into<String>) -> Feedback {
+        Feedback { message: message.into(), solved: false }
+    }
+
+    pub fn solved() -> Feedback {
+        Feedback { message: String::from("You win!"), solved: true }
+    }
+}
+
+pub trait Variant {
+    /// Explains what the secret looks like, shown at the start of the game.
+    fn intro(&self) -> String;
+
+    fn max_attempts(&self) -> u32;
+
+    /// Check a guess against the secret.
+    /// Input that isn't a valid guess returns Err with a message explaining
+    /// why, and doesn't use up an attempt.
+    fn check(&mut self, guess: &str) -> Result<Feedback, String>;
+
+    /// The secret, shown to the player when they run out of attempts.
+    fn reveal(&self) -> String;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VariantKind {
+    HotCold,
+    BullsAndCows,
+    FloatRange,
+}
+
+impl VariantKind {
+    pub fn parse(name: &str) -> Option<VariantKind> {
+        match name.trim().to_lowercase().as_str() {
+            "hotcold" | "hot-cold" => Some(VariantKind::HotCold),
+            "bulls" | "bulls-and-cows" => Some(VariantKind::BullsAndCows),
+            "float" => Some(VariantKind::FloatRange),
+            _ => None,
+        }
+    }
+
+    pub fn build<R: Rng>(&self, difficulty: Difficulty, rng: &mut R) -> Box<dyn Variant> {
+        match self {
+            VariantKind::HotCold => Box::new(HotCold::new(difficulty, rng)),
+            VariantKind::BullsAndCows => Box::new(BullsAndCows::new(difficulty, rng)),
+            VariantKind::FloatRange => Box::new(FloatRange::new(difficulty, rng)),
+        }
+    }
+}
+
+/// The same as play, but for any variant.
+/// Returns whether the player won.
+pub fn play_variant<R, I, O>(
+    kind: VariantKind,
+    difficulty: Option<Difficulty>,
+    rng: &mut R,
+    input: &mut I,
+    output: &mut O,
+) -> io::Result<bool>
+where
+    R: Rng,
+    I: BufRead,
+    O: Write,
+{
+    writeln!(output, "Guess the number!")?;
+
+    let difficulty = match difficulty {
+        Some(difficulty) => difficulty,
+        None => choose_difficulty(input, output)?,
+    };
+
+    let mut variant = kind.build(difficulty, rng);
+    writeln!(output, "{}", variant.intro())?;
+
+    let mut attempts_left = variant.max_attempts();
+
+    while attempts_left > 0 {
+        writeln!(output, "Please input your guess below ({} attempts left):", attempts_left)?;
+
+        let feedback = match variant.check(read_line(input)?.trim()) {
+            Ok(feedback) => feedback,
+            Err(message) => {
+                writeln!(output, "{}", message)?;
+                continue;
+            }
+        };
+
+        attempts_left -= 1;
+        writeln!(output, "{}", feedback.message)?;
+
+        if feedback.solved {
+            return Ok(true);
+        }
+    }
+
+    writeln!(output, "Out of attempts, you lose! The answer was {}.", variant.reveal())?;
+    Ok(false)
+}
@@ -0,0 +1,143 @@
+// Bulls and cows, the pen and paper game that Mastermind is based on.
+// The secret is a string of different digits. For each guess the player is
+// told how many digits are right and in the right place (bulls) and how many
+// are in the secret but in the wrong place (cows).
+// The difficulty sets how many digits the secret has.
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::{Feedback, Variant};
+use crate::Difficulty;
+
+pub struct BullsAndCows {
+    secret: Vec<u8>,
+    max_attempts: u32,
+}
+
+impl BullsAndCows {
+    pub fn new<R: Rng>(difficulty: Difficulty, rng: &mut R) -> BullsAndCows {
+        let length = match difficulty {
+            Difficulty::Easy => 3,
+            Difficulty::Medium => 4,
+            Difficulty::Hard => 5,
+        };
+
+        let mut digits: Vec<u8> = (0..10).collect();
+        digits.shuffle(rng);
+        digits.truncate(length);
+
+        BullsAndCows::with_secret(&digits, difficulty)
+    }
+
+    pub fn with_secret(secret: &[u8], difficulty: Difficulty) -> BullsAndCows {
+        BullsAndCows {
+            secret: secret.to_vec(),
+            max_attempts: difficulty.max_attempts() + 2,
+        }
+    }
+
+    fn parse_guess(&self, guess: &str) -> Result<Vec<u8>, String> {
+        let error = || format!("Please enter {} different digits!", self.secret.len());
+
+        let digits: Vec<u8> = guess
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(error)?;
+
+        let all_different = digits
+            .iter()
+            .enumerate()
+            .all(|(i, d)| !digits[..i].contains(d));
+
+        if digits.len() != self.secret.len() || !all_different {
+            return Err(error());
+        }
+
+        Ok(digits)
+    }
+
+    fn score(&self, guess: &[u8]) -> (usize, usize) {
+        let bulls = guess
+            .iter()
+            .zip(self.secret.iter())
+            .filter(|(g, s)| g == s)
+            .count();
+        let in_secret = guess.iter().filter(|g| self.secret.contains(g)).count();
+
+        (bulls, in_secret - bulls)
+    }
+}
+
+fn plural(count: usize, word: &str) -> String {
+    if count == 1 {
+        format!("{} {}", count, word)
+    } else {
+        format!("{} {}s", count, word)
+    }
+}
+
+impl Variant for BullsAndCows {
+    fn intro(&self) -> String {
+        format!(
+            "Bulls and cows: the secret is {} different digits. \
+A bull is a right digit in the right place, a cow is a right digit in the wrong place.",
+            self.secret.len(),
+        )
+    }
+
+    fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    fn check(&mut self, guess: &str) -> Result<Feedback, String> {
+        let guess = self.parse_guess(guess)?;
+        let (bulls, cows) = self.score(&guess);
+
+        if bulls == self.secret.len() {
+            return Ok(Feedback::solved());
+        }
+
+        Ok(Feedback::hint(format!("{}, {}", plural(bulls, "bull"), plural(cows, "cow"))))
+    }
+
+    fn reveal(&self) -> String {
+        self.secret.iter().map(|d| d.to_string()).collect()
+    }
+}
+
+#[cfg(test)]
+mod bulls_and_cows_tests {
+    use super::*;
+
+    #[test]
+    fn test_scoring() {
+        let mut game = BullsAndCows::with_secret(&[1, 2, 3, 4], Difficulty::Medium);
+
+        assert_eq!(game.check("5678").unwrap().message, "0 bulls, 0 cows");
+        assert_eq!(game.check("1243").unwrap().message, "2 bulls, 2 cows");
+        assert_eq!(game.check("1567").unwrap().message, "1 bull, 0 cows");
+        assert_eq!(game.check("4567").unwrap().message, "0 bulls, 1 cow");
+        assert!(game.check("1234").unwrap().solved);
+    }
+
+    #[test]
+    fn test_bad_guesses() {
+        let mut game = BullsAndCows::with_secret(&[1, 2, 3], Difficulty::Easy);
+
+        assert!(game.check("12").is_err());
+        assert!(game.check("1234").is_err());
+        assert!(game.check("112").is_err());
+        assert!(game.check("1a2").is_err());
+    }
+
+    #[test]
+    fn test_random_secret_has_different_digits() {
+        let mut rng = rand::thread_rng();
+        let game = BullsAndCows::new(Difficulty::Hard, &mut rng);
+
+        assert_eq!(game.secret.len(), 5);
+        assert!(game.parse_guess(&game.reveal()).is_ok());
+    }
+}
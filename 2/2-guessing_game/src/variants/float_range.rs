@@ -0,0 +1,105 @@
+// The classic game but with decimals: the secret has one decimal place and
+// a guess wins if it rounds to the secret.
+// Ten times as many possible answers needs about log2(10) more guesses to
+// bisect, so the player gets four extra attempts.
+
+use rand::Rng;
+
+use super::{Feedback, Variant};
+use crate::Difficulty;
+
+pub struct FloatRange {
+    difficulty: Difficulty,
+    // Stored in tenths so comparing guesses doesn't depend on how
+    // floating point numbers happen to round.
+    secret_tenths: u32,
+}
+
+impl FloatRange {
+    pub fn new<R: Rng>(difficulty: Difficulty, rng: &mut R) -> FloatRange {
+        let range = difficulty.range();
+        let tenths = rng.gen_range(range.start() * 10..=range.end() * 10);
+
+        FloatRange { difficulty, secret_tenths: tenths }
+    }
+
+    pub fn with_secret(difficulty: Difficulty, secret: f64) -> FloatRange {
+        FloatRange {
+            difficulty,
+            secret_tenths: (secret * 10.0).round() as u32,
+        }
+    }
+}
+
+impl Variant for FloatRange {
+    fn intro(&self) -> String {
+        let range = self.difficulty.range();
+        format!(
+            "Decimal guessing on {}: the number is between {:.1} and {:.1} with one decimal place.",
+            self.difficulty.name(),
+            *range.start() as f64,
+            *range.end() as f64,
+        )
+    }
+
+    fn max_attempts(&self) -> u32 {
+        self.difficulty.max_attempts() + 4
+    }
+
+    fn check(&mut self, guess: &str) -> Result<Feedback, String> {
+        let range = self.difficulty.range();
+        let guess: f64 = guess
+            .parse()
+            .map_err(|_| String::from("Could not parse number!"))?;
+
+        if !guess.is_finite() || guess < *range.start() as f64 || guess > *range.end() as f64 {
+            return Err(format!("That's not between {} and {}!", range.start(), range.end()));
+        }
+
+        let guess_tenths = (guess * 10.0).round() as u32;
+
+        Ok(match guess_tenths.cmp(&self.secret_tenths) {
+            std::cmp::Ordering::Less => Feedback::hint("Too small!"),
+            std::cmp::Ordering::Greater => Feedback::hint("Too big!"),
+            std::cmp::Ordering::Equal => Feedback::solved(),
+        })
+    }
+
+    fn reveal(&self) -> String {
+        format!("{:.1}", self.secret_tenths as f64 / 10.0)
+    }
+}
+
+#[cfg(test)]
+mod float_range_tests {
+    use super::*;
+
+    #[test]
+    fn test_feedback() {
+        let mut game = FloatRange::with_secret(Difficulty::Medium, 42.7);
+
+        assert_eq!(game.check("42.6").unwrap().message, "Too small!");
+        assert_eq!(game.check("42.8").unwrap().message, "Too big!");
+        assert!(game.check("42.74").unwrap().solved);
+        assert_eq!(game.reveal(), "42.7");
+    }
+
+    #[test]
+    fn test_bad_guesses() {
+        let mut game = FloatRange::with_secret(Difficulty::Easy, 1.0);
+
+        assert!(game.check("NaN").is_err());
+        assert!(game.check("0.9").is_err());
+        assert!(game.check("50.1").is_err());
+        assert!(game.check("one").is_err());
+    }
+
+    #[test]
+    fn test_random_secret_in_range() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let game = FloatRange::new(Difficulty::Easy, &mut rng);
+            assert!((10..=500).contains(&game.secret_tenths));
+        }
+    }
+}
@@ -0,0 +1,125 @@
+// Hot/cold: rather than saying which way to go, each guess only says whether
+// it was closer to the secret number than the last one.
+// The first guess has nothing to compare against, so it gets a rough idea of
+// how far away it was instead.
+// Without a direction this takes more guesses than the classic game, so the
+// player gets twice the usual number of attempts.
+
+use rand::Rng;
+
+use super::{Feedback, Variant};
+use crate::Difficulty;
+
+pub struct HotCold {
+    difficulty: Difficulty,
+    secret_number: u32,
+    previous_distance: Option<u32>,
+}
+
+impl HotCold {
+    pub fn new<R: Rng>(difficulty: Difficulty, rng: &mut R) -> HotCold {
+        HotCold::with_secret(difficulty, rng.gen_range(difficulty.range()))
+    }
+
+    pub fn with_secret(difficulty: Difficulty, secret_number: u32) -> HotCold {
+        HotCold {
+            difficulty,
+            secret_number,
+            previous_distance: None,
+        }
+    }
+
+    // How far away a guess is as a fraction of the whole range.
+    fn first_hint(&self, distance: u32) -> &'static str {
+        let size = self.difficulty.range().count() as f64;
+        let fraction = distance as f64 / size;
+
+        if fraction <= 0.05 {
+            "Hot!"
+        } else if fraction <= 0.2 {
+            "Warm."
+        } else {
+            "Cold."
+        }
+    }
+}
+
+impl Variant for HotCold {
+    fn intro(&self) -> String {
+        let range = self.difficulty.range();
+        format!(
+            "Hot/cold on {}: the number is between {} and {}. \
+Each guess says if you're warmer or colder than the last.",
+            self.difficulty.name(),
+            range.start(),
+            range.end(),
+        )
+    }
+
+    fn max_attempts(&self) -> u32 {
+        self.difficulty.max_attempts() * 2
+    }
+
+    fn check(&mut self, guess: &str) -> Result<Feedback, String> {
+        let range = self.difficulty.range();
+        let guess: u32 = guess
+            .parse()
+            .map_err(|_| String::from("Could not parse number!"))?;
+
+        if !range.contains(&guess) {
+            return Err(format!("That's not between {} and {}!", range.start(), range.end()));
+        }
+
+        let distance = guess.abs_diff(self.secret_number);
+        if distance == 0 {
+            return Ok(Feedback::solved());
+        }
+
+        let hint = match self.previous_distance {
+            None => self.first_hint(distance),
+            Some(previous) if distance < previous => "Warmer!",
+            Some(previous) if distance > previous => "Colder!",
+            Some(_) => "No warmer, no colder.",
+        };
+        self.previous_distance = Some(distance);
+
+        Ok(Feedback::hint(hint))
+    }
+
+    fn reveal(&self) -> String {
+        self.secret_number.to_string()
+    }
+}
+
+#[cfg(test)]
+mod hot_cold_tests {
+    use super::*;
+
+    #[test]
+    fn test_warmer_and_colder() {
+        let mut game = HotCold::with_secret(Difficulty::Medium, 50);
+
+        assert_eq!(game.check("10").unwrap().message, "Cold.");
+        assert_eq!(game.check("40").unwrap().message, "Warmer!");
+        assert_eq!(game.check("70").unwrap().message, "Colder!");
+        assert_eq!(game.check("30").unwrap().message, "No warmer, no colder.");
+        assert!(game.check("50").unwrap().solved);
+    }
+
+    #[test]
+    fn test_first_hint_distance() {
+        let mut game = HotCold::with_secret(Difficulty::Medium, 50);
+        assert_eq!(game.check("53").unwrap().message, "Hot!");
+
+        let mut game = HotCold::with_secret(Difficulty::Medium, 50);
+        assert_eq!(game.check("65").unwrap().message, "Warm.");
+    }
+
+    #[test]
+    fn test_bad_guesses() {
+        let mut game = HotCold::with_secret(Difficulty::Easy, 5);
+
+        assert!(game.check("five").is_err());
+        assert!(game.check("51").is_err());
+    }
+}
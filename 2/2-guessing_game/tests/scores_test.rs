@@ -0,0 +1,109 @@
+// Tests for the high score file, run against real files in the system's
+// temporary directory.
+
+use std::env;
+use std::fs;
+use std::io::Cursor;
+use std::path::PathBuf;
+
+use guessing_game::{record_win, Difficulty, Game, ScoreTable};
+
+// Each test gets its own file so the tests can run in parallel.
+fn temp_scores_path(test_name: &str) -> PathBuf {
+    let path = env::temp_dir().join(format!(
+        "guessing_game_{}_{}.txt",
+        test_name,
+        std::process::id(),
+    ));
+    let _ = fs::remove_file(&path);
+    path
+}
+
+fn won_game(difficulty: Difficulty, guesses: u32) -> Game {
+    let mut game = Game::new(difficulty, 1);
+    for _ in 1..guesses {
+        game.guess(2);
+    }
+    game.guess(1);
+    game
+}
+
+#[test]
+fn test_missing_file_is_empty_table() {
+    let path = temp_scores_path("missing");
+
+    let (table, warnings) = ScoreTable::load(&path).unwrap();
+
+    assert_eq!(table, ScoreTable::new());
+    assert!(warnings.is_empty());
+}
+
+#[test]
+fn test_record_win_adds_to_file() {
+    let path = temp_scores_path("record");
+    fs::write(&path, "easy\t5\t1000\tFerris\nnot a score\n").unwrap();
+
+    let mut input = Cursor::new("Bill\n".as_bytes());
+    let mut output = Vec::new();
+    record_win(&path, &won_game(Difficulty::Easy, 2), &mut input, &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("Warning: Skipped line 2 of the scores file"));
+    let bill = output.find("Bill").unwrap();
+    let ferris = output.find("Ferris").unwrap();
+    assert!(bill < ferris, "Fewer guesses should rank higher:\n{}", output);
+
+    // The bad line is dropped when the file is saved again.
+    let (table, warnings) = ScoreTable::load(&path).unwrap();
+    assert_eq!(table.top(Difficulty::Easy).len(), 2);
+    assert!(warnings.is_empty());
+
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_unreadable_file_is_moved_aside() {
+    let path = temp_scores_path("unreadable");
+    let mut backup = path.clone().into_os_string();
+    backup.push(".corrupt");
+    let backup = PathBuf::from(backup);
+    fs::write(&path, [0xff, 0xfe, 0x00]).unwrap();
+
+    let (table, warnings) = ScoreTable::load(&path).unwrap();
+
+    assert_eq!(table, ScoreTable::new());
+    assert_eq!(warnings.len(), 1);
+    assert!(!path.exists());
+    assert_eq!(fs::read(&backup).unwrap(), vec![0xff, 0xfe, 0x00]);
+
+    fs::remove_file(&backup).unwrap();
+}
+
+#[test]
+fn test_unreadable_path_is_left_alone() {
+    // A directory can't be read as a file, but nothing is wrong with it.
+    let path = temp_scores_path("directory");
+    fs::create_dir(&path).unwrap();
+
+    let mut input = Cursor::new("Bill\n".as_bytes());
+    let mut output = Vec::new();
+    record_win(&path, &won_game(Difficulty::Easy, 2), &mut input, &mut output).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(ScoreTable::load(&path).is_err());
+    assert!(output.contains("your score was not saved"), "{}", output);
+    assert!(path.is_dir());
+
+    fs::remove_dir(&path).unwrap();
+}
+
+#[test]
+fn test_no_name_skips_saving() {
+    let path = temp_scores_path("no_name");
+
+    let mut input = Cursor::new("".as_bytes());
+    let mut output = Vec::new();
+    record_win(&path, &won_game(Difficulty::Easy, 2), &mut input, &mut output).unwrap();
+
+    assert!(!path.exists());
+}
@@ -0,0 +1,103 @@
+// These tests start a server on a free localhost port and connect to it as
+// a couple of players.
+// As in transcript_test.rs the secret numbers are worked out from the same
+// seed the server is given.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::thread;
+use std::time::Duration;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use guessing_game::{serve, Difficulty};
+
+const SEED: u64 = 31;
+
+struct Client {
+    reader: BufReader<TcpStream>,
+    writer: TcpStream,
+}
+
+impl Client {
+    fn connect(port: u16) -> Client {
+        let stream = TcpStream::connect(("127.0.0.1", port)).unwrap();
+        stream.set_read_timeout(Some(Duration::from_secs(5))).unwrap();
+
+        Client {
+            reader: BufReader::new(stream.try_clone().unwrap()),
+            writer: stream,
+        }
+    }
+
+    fn send(&mut self, line: &str) {
+        writeln!(self.writer, "{}", line).unwrap();
+    }
+
+    fn receive(&mut self) -> String {
+        let mut line = String::new();
+        self.reader.read_line(&mut line).unwrap();
+        line.trim_end().to_string()
+    }
+}
+
+fn start_server(difficulty: Difficulty) -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    thread::spawn(move || serve(listener, difficulty, StdRng::seed_from_u64(SEED)));
+
+    port
+}
+
+#[test]
+fn test_race_between_two_players() {
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let first_secret = rng.gen_range(Difficulty::Medium.range());
+    let second_secret = rng.gen_range(Difficulty::Medium.range());
+
+    let port = start_server(Difficulty::Medium);
+    let mut alice = Client::connect(port);
+    assert_eq!(alice.receive(), "Welcome Player 1! Round 1: guess the number between 1 and 100.");
+    let mut bob = Client::connect(port);
+    assert_eq!(bob.receive(), "Welcome Player 2! Round 1: guess the number between 1 and 100.");
+
+    alice.send("lots");
+    assert_eq!(alice.receive(), "Could not parse number!");
+    alice.send("101");
+    assert_eq!(alice.receive(), "That's not between 1 and 100!");
+
+    let wrong = if first_secret == 100 { 99 } else { 100 };
+    alice.send(&wrong.to_string());
+    let expected = if wrong > first_secret { "Too big!" } else { "Too small!" };
+    assert_eq!(alice.receive(), expected);
+
+    bob.send(&first_secret.to_string());
+    let win = format!("Player 2 guessed {} and wins round 1!", first_secret);
+    let next = "Round 2: guess the number between 1 and 100.";
+    for client in [&mut alice, &mut bob] {
+        assert_eq!(client.receive(), win);
+        assert_eq!(client.receive(), next);
+    }
+
+    alice.send(&second_secret.to_string());
+    let win = format!("Player 1 guessed {} and wins round 2!", second_secret);
+    assert_eq!(bob.receive(), win);
+}
+
+#[test]
+fn test_disconnected_player_does_not_stop_the_round() {
+    let secret = StdRng::seed_from_u64(SEED).gen_range(Difficulty::Easy.range());
+
+    let port = start_server(Difficulty::Easy);
+    let mut leaver = Client::connect(port);
+    leaver.receive();
+    drop(leaver);
+
+    let mut stayer = Client::connect(port);
+    stayer.receive();
+    stayer.send(&secret.to_string());
+
+    assert_eq!(stayer.receive(), format!("Player 2 guessed {} and wins round 1!", secret));
+}
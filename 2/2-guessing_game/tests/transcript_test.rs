@@ -0,0 +1,230 @@
+// These tests replay a script of guesses through the game and check every
+// line that it prints.
+// The secret number is worked out from the same seed the game is given, so
+// the tests don't depend on exactly which number rand picks for a seed.
+
+use std::io::{Cursor, ErrorKind};
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use guessing_game::variants::hot_cold::HotCold;
+use guessing_game::{auto_play, play, play_variant, reverse_play, Difficulty, Game, Variant, VariantKind};
+
+const SEED: u64 = 2021;
+
+fn secret_for(difficulty: Difficulty) -> u32 {
+    StdRng::seed_from_u64(SEED).gen_range(difficulty.range())
+}
+
+// A seed whose secret number isn't at either end of the range, for scripts
+// that guess one either side of it.
+fn seed_away_from_edges(difficulty: Difficulty) -> u64 {
+    let range = difficulty.range();
+    (SEED..)
+        .find(|seed| {
+            let secret = StdRng::seed_from_u64(*seed).gen_range(range.clone());
+            secret != *range.start() && secret != *range.end()
+        })
+        .unwrap()
+}
+
+fn run_script(difficulty: Option<Difficulty>, script: &str) -> (std::io::Result<Game>, String) {
+    run_script_with_seed(SEED, difficulty, script)
+}
+
+fn run_script_with_seed(seed: u64, difficulty: Option<Difficulty>, script: &str) -> (std::io::Result<Game>, String) {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut input = Cursor::new(script.as_bytes());
+    let mut output = Vec::new();
+
+    let result = play(difficulty, &mut rng, &mut input, &mut output);
+
+    (result, String::from_utf8(output).unwrap())
+}
+
+#[test]
+fn test_win_transcript() {
+    let seed = seed_away_from_edges(Difficulty::Easy);
+    let secret = StdRng::seed_from_u64(seed).gen_range(Difficulty::Easy.range());
+    let script = format!("{}\n{}\nfifty\n{}\n", secret - 1, secret + 1, secret);
+
+    let (result, transcript) = run_script_with_seed(seed, Some(Difficulty::Easy), &script);
+
+    let expected = format!(
+        "Guess the number!
+Playing on easy: the number is between 1 and 50 and you have 10 attempts.
+Please input your guess below (10 attempts left):
+You guessed {}
+Too small!
+Please input your guess below (9 attempts left):
+You guessed {}
+Too big!
+Please input your guess below (8 attempts left):
+Could not parse number!
+Please input your guess below (8 attempts left):
+You guessed {}
+You win!
+",
+        secret - 1,
+        secret + 1,
+        secret,
+    );
+
+    assert_eq!(transcript, expected);
+    assert_eq!(result.unwrap().attempts(), 3);
+}
+
+#[test]
+fn test_menu_and_lose_transcript() {
+    let secret = secret_for(Difficulty::Medium);
+    let wrong = if secret == 1 { 2 } else { 1 };
+    let mut script = String::from("9\nmedium\n");
+    for _ in 0..7 {
+        script.push_str(&format!("{}\n", wrong));
+    }
+
+    let (result, transcript) = run_script(None, &script);
+
+    let menu = "Choose a difficulty:
+  1) easy (1-50, 10 attempts)
+  2) medium (1-100, 7 attempts)
+  3) hard (1-1000, 10 attempts)
+";
+    let feedback = if wrong < secret { "Too small!" } else { "Too big!" };
+    let mut expected = format!(
+        "Guess the number!\n{}That's not one of the options!\n{}\
+Playing on medium: the number is between 1 and 100 and you have 7 attempts.\n",
+        menu, menu,
+    );
+    for left in (1..=7).rev() {
+        expected.push_str(&format!(
+            "Please input your guess below ({} attempts left):\nYou guessed {}\n",
+            left, wrong,
+        ));
+        if left > 1 {
+            expected.push_str(feedback);
+            expected.push('\n');
+        }
+    }
+    expected.push_str(&format!("Out of attempts, you lose! The number was {}.\n", secret));
+
+    assert_eq!(transcript, expected);
+    assert!(result.unwrap().is_over());
+}
+
+#[test]
+fn test_same_seed_same_game() {
+    let script = "1\n2\n3\n";
+
+    let (_, first) = run_script(Some(Difficulty::Hard), script);
+    let (_, second) = run_script(Some(Difficulty::Hard), script);
+
+    assert_eq!(first, second);
+}
+
+#[test]
+fn test_script_running_out() {
+    let (result, _) = run_script(Some(Difficulty::Hard), "");
+
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn test_auto_play_wins() {
+    for difficulty in Difficulty::ALL.iter() {
+        let mut rng = StdRng::seed_from_u64(SEED);
+        let mut output = Vec::new();
+
+        let game = auto_play(*difficulty, &mut rng, &mut output).unwrap();
+
+        assert!(game.is_won());
+        assert_eq!(game.secret_number(), secret_for(*difficulty));
+    }
+}
+
+#[test]
+fn test_reverse_transcript() {
+    // The player is thinking of 30.
+    let mut input = Cursor::new("what\ns\nb\nb\ns\ns\nc\n".as_bytes());
+    let mut output = Vec::new();
+
+    let answer = reverse_play(Some(Difficulty::Easy), &mut input, &mut output).unwrap();
+
+    assert_eq!(answer, Some(30));
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "Think of a number between 1 and 50 and I'll guess it.
+Is it 25? (s = too small, b = too big, c = correct)
+Please answer s, b or c.
+Is it 25? (s = too small, b = too big, c = correct)
+Is it 38? (s = too small, b = too big, c = correct)
+Is it 31? (s = too small, b = too big, c = correct)
+Is it 28? (s = too small, b = too big, c = correct)
+Is it 29? (s = too small, b = too big, c = correct)
+Is it 30? (s = too small, b = too big, c = correct)
+Got it in 6 guesses!
+"
+    );
+}
+
+#[test]
+fn test_reverse_detects_lies() {
+    let mut input = Cursor::new("s\ns\ns\ns\ns\ns\ns\n".as_bytes());
+    let mut output = Vec::new();
+
+    let answer = reverse_play(Some(Difficulty::Easy), &mut input, &mut output).unwrap();
+    let transcript = String::from_utf8(output).unwrap();
+
+    assert_eq!(answer, None);
+    assert!(transcript.ends_with("Your answers don't add up, no number fits all of them!\n"));
+}
+
+#[test]
+fn test_hot_cold_variant_transcript() {
+    let secret = secret_for(Difficulty::Easy);
+    // Guesses that are always further away, then the answer.
+    let (near, far) = if secret > 25 { (secret - 1, 1) } else { (secret + 1, 50) };
+    let script = format!("{}\n{}\nwarm\n{}\n", near, far, secret);
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut input = Cursor::new(script.as_bytes());
+    let mut output = Vec::new();
+    let won = play_variant(VariantKind::HotCold, Some(Difficulty::Easy), &mut rng, &mut input, &mut output).unwrap();
+
+    let intro = HotCold::with_secret(Difficulty::Easy, secret).intro();
+    let expected = format!(
+        "Guess the number!
+{}
+Please input your guess below (20 attempts left):
+Hot!
+Please input your guess below (19 attempts left):
+Colder!
+Please input your guess below (18 attempts left):
+Could not parse number!
+Please input your guess below (18 attempts left):
+You win!
+",
+        intro,
+    );
+
+    assert!(won);
+    assert_eq!(String::from_utf8(output).unwrap(), expected);
+}
+
+#[test]
+fn test_variant_lose_reveals_answer() {
+    let secret = secret_for(Difficulty::Easy);
+    let wrong = if secret == 1 { 2 } else { 1 };
+
+    let mut rng = StdRng::seed_from_u64(SEED);
+    let mut input = Cursor::new(format!("{}\n", wrong).repeat(20).into_bytes());
+    let mut output = Vec::new();
+    let won = play_variant(VariantKind::HotCold, Some(Difficulty::Easy), &mut rng, &mut input, &mut output).unwrap();
+
+    let transcript = String::from_utf8(output).unwrap();
+    let expected = format!("Out of attempts, you lose! The answer was {}.\n", secret);
+
+    assert!(!won);
+    assert!(transcript.ends_with(&expected), "{}", transcript);
+}
@@ -0,0 +1,326 @@
+// Batch mode converts a whole column of a CSV file in one go, for when
+// typing numbers into the prompt one at a time would take all day:
+//
+//     fahrenheit_converter --csv readings.csv --column temp --from degF --to degC
+//
+// The converted values are added as a new column unless --replace is given.
+// Rows where the value can't be converted are still written out, with an
+// empty cell, and every one of them is reported with its line number.
+
+use std::error::Error;
+use std::fmt;
+use std::io::{self, BufRead, Write};
+
+use crate::csv::{join_line, split_line};
+use crate::{UnitError, UnitRegistry};
+
+#[derive(Debug, PartialEq)]
+pub struct BatchConfig {
+    pub input_path: String,
+    pub output_path: Option<String>,
+    pub column: String,
+    pub from: String,
+    pub to: String,
+    pub replace: bool,
+    pub new_column: Option<String>,
+}
+
+impl BatchConfig {
+    /// Returns Ok(None) when there are no arguments at all, which means the
+    /// converter should run interactively as usual.
+    pub fn new<I>(args: I) -> Result<Option<BatchConfig>, &'static str>
+    where I: Iterator<Item = String>
+    {
+        let mut args = args.skip(1).peekable();
+        if args.peek().is_none() {
+            return Ok(None);
+        }
+
+        let mut input_path = None;
+        let mut output_path = None;
+        let mut column = None;
+        let mut from = None;
+        let mut to = None;
+        let mut replace = false;
+        let mut new_column = None;
+
+        while let Some(arg) = args.next() {
+            let slot = match arg.as_str() {
+                "--replace" => {
+                    replace = true;
+                    continue;
+                }
+                "--csv" => &mut input_path,
+                "--output" | "-o" => &mut output_path,
+                "--column" => &mut column,
+                "--from" => &mut from,
+                "--to" => &mut to,
+                "--new-column" => &mut new_column,
+                _ => return Err("Unknown argument!"),
+            };
+            *slot = Some(args.next().ok_or("Missing value after an argument!")?);
+        }
+
+        if replace && new_column.is_some() {
+            return Err("--replace and --new-column can't be used together!");
+        }
+
+        Ok(Some(BatchConfig {
+            input_path: input_path.ok_or("Batch mode needs --csv FILE!")?,
+            output_path,
+            column: column.ok_or("Batch mode needs --column NAME!")?,
+            from: from.ok_or("Batch mode needs --from UNIT!")?,
+            to: to.ok_or("Batch mode needs --to UNIT!")?,
+            replace,
+            new_column,
+        }))
+    }
+}
+
+#[derive(Debug)]
+pub enum BatchError {
+    Io(io::Error),
+    Unit(UnitError),
+    EmptyFile,
+    BadHeader(&'static str),
+    MissingColumn(String),
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BatchError::Io(e) => write!(f, "{}", e),
+            BatchError::Unit(e) => write!(f, "{}", e),
+            BatchError::EmptyFile => write!(f, "The CSV file is empty!"),
+            BatchError::BadHeader(reason) => write!(f, "Could not read the header row: {}!", reason),
+            BatchError::MissingColumn(column) => write!(f, "There is no column called '{}'!", column),
+        }
+    }
+}
+
+impl Error for BatchError {}
+
+impl From<io::Error> for BatchError {
+    fn from(e: io::Error) -> BatchError {
+        BatchError::Io(e)
+    }
+}
+
+impl From<UnitError> for BatchError {
+    fn from(e: UnitError) -> BatchError {
+        BatchError::Unit(e)
+    }
+}
+
+/// A row that couldn't be converted. Line numbers count from 1 and include
+/// the header, so they match what a text editor shows.
+#[derive(Debug, PartialEq)]
+pub struct RowError {
+    pub line: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub struct BatchReport {
+    pub converted: usize,
+    pub failures: Vec<RowError>,
+}
+
+pub fn convert_csv<I, O>(
+    config: &BatchConfig,
+    registry: &UnitRegistry,
+    input: &mut I,
+    output: &mut O,
+) -> Result<BatchReport, BatchError>
+where
+    I: BufRead,
+    O: Write,
+{
+    // Check the units before reading anything so a typo in --to doesn't
+    // produce a file full of failed rows.
+    registry.convert(1.0, &config.from, &config.to)?;
+
+    let mut lines = input.lines().enumerate();
+    let mut header = match lines.next() {
+        Some((_, line)) => split_line(&line?).map_err(BatchError::BadHeader)?,
+        None => return Err(BatchError::EmptyFile),
+    };
+
+    let index = header
+        .iter()
+        .position(|name| name.trim() == config.column)
+        .ok_or_else(|| BatchError::MissingColumn(config.column.clone()))?;
+
+    if !config.replace {
+        let name = match &config.new_column {
+            Some(name) => name.clone(),
+            None => format!("{} ({})", config.column, config.to),
+        };
+        header.push(name);
+    }
+    writeln!(output, "{}", join_line(&header))?;
+
+    let mut report = BatchReport::default();
+
+    for (number, line) in lines {
+        let line = line?;
+        let line_number = number + 1;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let mut fields = match split_line(&line) {
+            Ok(fields) => fields,
+            Err(reason) => {
+                // The row can't be split into fields so it is passed
+                // through untouched.
+                report.failures.push(RowError { line: line_number, message: reason.to_string() });
+                writeln!(output, "{}", line)?;
+                continue;
+            }
+        };
+
+        let cell = match convert_field(fields.get(index), config, registry) {
+            Ok(value) => {
+                report.converted += 1;
+                value.to_string()
+            }
+            Err(message) => {
+                report.failures.push(RowError { line: line_number, message });
+                String::new()
+            }
+        };
+
+        // Short rows are padded so every value lines up with its header.
+        let columns = if config.replace { header.len() } else { header.len() - 1 };
+        if fields.len() < columns {
+            fields.resize(columns, String::new());
+        }
+
+        if config.replace {
+            fields[index] = cell;
+        } else {
+            fields.push(cell);
+        }
+        writeln!(output, "{}", join_line(&fields))?;
+    }
+
+    Ok(report)
+}
+
+fn convert_field(field: Option<&String>, config: &BatchConfig, registry: &UnitRegistry) -> Result<f64, String> {
+    let field = field.ok_or_else(|| format!("row has no '{}' column", config.column))?;
+    let field = field.trim();
+
+    let value: f64 = field
+        .parse()
+        .map_err(|_| format!("could not parse '{}' as a number", field))?;
+
+    if !value.is_finite() {
+        return Err(format!("could not parse '{}' as a number", field));
+    }
+
+    registry
+        .convert(value, &config.from, &config.to)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod batch_tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> impl Iterator<Item = String> {
+        let mut all = vec!["fahrenheit_converter".to_string()];
+        all.extend(args.iter().map(|a| a.to_string()));
+        all.into_iter()
+    }
+
+    fn test_config(replace: bool) -> BatchConfig {
+        BatchConfig {
+            input_path: String::from("in.csv"),
+            output_path: None,
+            column: String::from("temp"),
+            from: String::from("degF"),
+            to: String::from("degC"),
+            replace,
+            new_column: None,
+        }
+    }
+
+    fn run(config: &BatchConfig, csv: &str) -> (Result<BatchReport, BatchError>, String) {
+        let mut output = Vec::new();
+        let result = convert_csv(config, &UnitRegistry::default(), &mut csv.as_bytes(), &mut output);
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_no_arguments_is_interactive() {
+        assert_eq!(BatchConfig::new(args(&[])), Ok(None));
+    }
+
+    #[test]
+    fn test_arguments() {
+        let config = BatchConfig::new(args(&[
+            "--csv", "in.csv", "--column", "temp", "--from", "degF", "--to", "degC", "--replace",
+        ]));
+
+        assert_eq!(config, Ok(Some(test_config(true))));
+        assert!(BatchConfig::new(args(&["--csv", "in.csv"])).is_err());
+        assert!(BatchConfig::new(args(&["--csv"])).is_err());
+        assert!(BatchConfig::new(args(&["--colour", "red"])).is_err());
+    }
+
+    #[test]
+    fn test_append_column() {
+        let (result, output) = run(&test_config(false), "city,temp\nOslo,32\n\"Paris, FR\",212\n");
+
+        assert_eq!(result.unwrap(), BatchReport { converted: 2, failures: Vec::new() });
+        assert_eq!(output, "city,temp,temp (degC)\nOslo,32,0\n\"Paris, FR\",212,100\n");
+    }
+
+    #[test]
+    fn test_replace_column() {
+        let (_, output) = run(&test_config(true), "city,temp\nOslo,32\n");
+
+        assert_eq!(output, "city,temp\nOslo,0\n");
+    }
+
+    #[test]
+    fn test_replace_pads_short_rows() {
+        let (result, output) = run(&test_config(true), "city,temp,notes\nOslo,32\nLima\n");
+
+        let report = result.unwrap();
+        assert_eq!(report.converted, 1);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].line, 3);
+        assert_eq!(output, "city,temp,notes\nOslo,0,\nLima,,\n");
+    }
+
+    #[test]
+    fn test_failures_are_reported() {
+        let csv = "city,temp\nOslo,cold\n\nRome,-500\nLima\nBern,\"50\nOslo,32\n";
+        let (result, output) = run(&test_config(false), csv);
+        let report = result.unwrap();
+
+        let lines: Vec<usize> = report.failures.iter().map(|f| f.line).collect();
+        assert_eq!(lines, vec![2, 4, 5, 6]);
+        assert!(report.failures[0].message.contains("'cold'"));
+        assert!(report.failures[1].message.contains("absolute zero"));
+        assert!(report.failures[2].message.contains("no 'temp' column"));
+        assert_eq!(report.converted, 1);
+        assert_eq!(
+            output,
+            "city,temp,temp (degC)\nOslo,cold,\nRome,-500,\nLima,,\nBern,\"50\nOslo,32,0\n"
+        );
+    }
+
+    #[test]
+    fn test_bad_setup() {
+        assert!(matches!(run(&test_config(false), "city,temperature\n").0, Err(BatchError::MissingColumn(_))));
+        assert!(matches!(run(&test_config(false), "").0, Err(BatchError::EmptyFile)));
+
+        let mut config = test_config(false);
+        config.to = String::from("kg");
+        assert!(matches!(run(&config, "temp\n1\n").0, Err(BatchError::Unit(_))));
+    }
+}
@@ -0,0 +1,74 @@
+// Just enough CSV to read and write the files batch mode works on.
+// Fields are separated by commas and may be wrapped in double quotes, in
+// which case they can contain commas and "" stands for a single quote.
+// Quoted fields spanning more than one line aren't supported.
+
+/// Split one line of a CSV file into its fields.
+/// Returns an error message if a quoted field is never closed.
+pub fn split_line(line: &str) -> Result<Vec<String>, &'static str> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut in_quotes = false;
+
+    while let Some(c) = chars.next() {
+        match (c, in_quotes) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            ('"', true) => in_quotes = false,
+            ('"', false) if field.is_empty() => in_quotes = true,
+            (',', false) => fields.push(std::mem::take(&mut field)),
+            (c, _) => field.push(c),
+        }
+    }
+
+    if in_quotes {
+        return Err("a quoted field is never closed");
+    }
+
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Join fields into a line, quoting any that need it.
+pub fn join_line(fields: &[String]) -> String {
+    fields
+        .iter()
+        .map(|field| {
+            if field.contains([',', '"', '\n', '\r']) {
+                format!("\"{}\"", field.replace('"', "\"\""))
+            } else {
+                field.clone()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod csv_tests {
+    use super::*;
+
+    #[test]
+    fn test_split_plain_and_quoted() {
+        assert_eq!(split_line("a,b,,c").unwrap(), vec!["a", "b", "", "c"]);
+        assert_eq!(
+            split_line("\"Smith, J\",\"say \"\"hi\"\"\",3").unwrap(),
+            vec!["Smith, J", "say \"hi\"", "3"]
+        );
+    }
+
+    #[test]
+    fn test_unclosed_quote() {
+        assert!(split_line("\"oops,1").is_err());
+    }
+
+    #[test]
+    fn test_join_round_trip() {
+        let fields = vec![String::from("Smith, J"), String::from("say \"hi\""), String::from("3")];
+
+        assert_eq!(split_line(&join_line(&fields)).unwrap(), fields);
+    }
+}
@@ -0,0 +1,347 @@
+// Arithmetic in place of a plain number, so "(98.6 + 1.2) * 2" can be
+// converted rather than rejected.
+//
+// This is a recursive descent parser: each level of precedence is a method
+// that parses the level above it and then loops over its own operators.
+//
+//     expression := term (("+" | "-") term)*
+//     term       := unary (("*" | "/") unary)*
+//     unary      := ("-" | "+") unary | power
+//     power      := primary ("^" unary)?
+//     primary    := number | name "(" expression ("," expression)* ")" | "(" expression ")"
+//
+// The value is worked out while parsing rather than building a tree first,
+// since nothing else needs the tree.
+// Errors carry the position (in characters) where things went wrong so the
+// REPL can point at it.
+
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpressionError {
+    pub position: usize,
+    pub message: String,
+}
+
+impl ExpressionError {
+    fn new(position: usize, message: impl Into<String>) -> ExpressionError {
+        ExpressionError { position, message: message.into() }
+    }
+
+    /// A line with a ^ under the position of the error, to print beneath
+    /// the input.
+    pub fn pointer(&self) -> String {
+        format!("{}^", " ".repeat(self.position))
+    }
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} at position {}!", self.message, self.position + 1)
+    }
+}
+
+impl Error for ExpressionError {}
+
+/// Evaluate the whole of the text as an expression.
+pub fn evaluate(text: &str) -> Result<f64, ExpressionError> {
+    let mut parser = Parser::new(text);
+    let value = parser.expression()?;
+
+    parser.skip_whitespace();
+    if let Some(c) = parser.peek() {
+        return Err(ExpressionError::new(parser.pos, format!("Unexpected '{}'", c)));
+    }
+
+    Ok(value)
+}
+
+/// Evaluate as much of the start of the text as makes an expression and
+/// return the value along with the rest of the text, e.g. "(1 + 2) km/h"
+/// gives 3 and "km/h".
+pub fn evaluate_prefix(text: &str) -> Result<(f64, &str), ExpressionError> {
+    let mut parser = Parser::new(text);
+    let value = parser.expression()?;
+
+    let rest: usize = parser.chars[..parser.pos].iter().map(|c| c.len_utf8()).sum();
+    Ok((value, text[rest..].trim()))
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn new(text: &str) -> Parser {
+        Parser { chars: text.chars().collect(), pos: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    // Move past the next character if it is one of the given operators.
+    fn operator(&mut self, operators: &[char]) -> Option<(char, usize)> {
+        self.skip_whitespace();
+        let c = self.peek().filter(|c| operators.contains(c))?;
+        let position = self.pos;
+        self.pos += 1;
+        Some((c, position))
+    }
+
+    fn expression(&mut self) -> Result<f64, ExpressionError> {
+        let mut value = self.term()?;
+
+        while let Some((operator, position)) = self.operator(&['+', '-']) {
+            let right = self.term()?;
+            value = if operator == '+' { value + right } else { value - right };
+            value = check_finite(value, position)?;
+        }
+
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<f64, ExpressionError> {
+        let mut value = self.unary()?;
+
+        while let Some((operator, position)) = self.operator(&['*', '/']) {
+            let right = self.unary()?;
+            if operator == '*' {
+                value *= right;
+            } else if right == 0.0 {
+                return Err(ExpressionError::new(position, "Division by zero"));
+            } else {
+                value /= right;
+            }
+            value = check_finite(value, position)?;
+        }
+
+        Ok(value)
+    }
+
+    fn unary(&mut self) -> Result<f64, ExpressionError> {
+        match self.operator(&['-', '+']) {
+            Some(('-', _)) => Ok(-self.unary()?),
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    // ^ binds tighter than unary minus on its left, so -2^2 is -4, but its
+    // right hand side can be negative, as in 2^-1.
+    fn power(&mut self) -> Result<f64, ExpressionError> {
+        let base = self.primary()?;
+
+        match self.operator(&['^']) {
+            Some((_, position)) => {
+                let value = base.powf(self.unary()?);
+                check_finite(value, position)
+            }
+            None => Ok(base),
+        }
+    }
+
+    fn primary(&mut self) -> Result<f64, ExpressionError> {
+        self.skip_whitespace();
+        let start = self.pos;
+
+        match self.peek() {
+            Some('(') => {
+                self.pos += 1;
+                let value = self.expression()?;
+                self.expect(')')?;
+                Ok(value)
+            }
+            Some(c) if c.is_ascii_digit() || c == '.' => self.number(),
+            Some(c) if c.is_alphabetic() => {
+                while self.peek().is_some_and(char::is_alphanumeric) {
+                    self.pos += 1;
+                }
+                let name: String = self.chars[start..self.pos].iter().collect();
+                self.function(&name, start)
+            }
+            Some(c) => Err(ExpressionError::new(start, format!("Expected a number but found '{}'", c))),
+            None => Err(ExpressionError::new(start, "Expected a number")),
+        }
+    }
+
+    fn number(&mut self) -> Result<f64, ExpressionError> {
+        let start = self.pos;
+
+        while self.peek().is_some_and(|c| c.is_ascii_digit() || c == '.') {
+            self.pos += 1;
+        }
+
+        // An exponent such as 1e3 or 2.5E-4, only if digits follow the e.
+        if let Some('e') | Some('E') = self.peek() {
+            let sign = matches!(self.chars.get(self.pos + 1), Some('+') | Some('-'));
+            let digits_at = self.pos + 1 + sign as usize;
+            if self.chars.get(digits_at).is_some_and(char::is_ascii_digit) {
+                self.pos = digits_at;
+                while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+                    self.pos += 1;
+                }
+            }
+        }
+
+        // Rust parses a number too big for an f64, such as 1e999, as infinity.
+        let text: String = self.chars[start..self.pos].iter().collect();
+        match text.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            Ok(_) => Err(ExpressionError::new(start, format!("'{}' is too big", text))),
+            Err(_) => Err(ExpressionError::new(start, format!("'{}' is not a valid number", text))),
+        }
+    }
+
+    fn function(&mut self, name: &str, position: usize) -> Result<f64, ExpressionError> {
+        self.skip_whitespace();
+        if self.peek() != Some('(') {
+            return Err(ExpressionError::new(position, format!("Unknown name '{}'", name)));
+        }
+        self.pos += 1;
+
+        let mut args = vec![self.expression()?];
+        while self.operator(&[',']).is_some() {
+            args.push(self.expression()?);
+        }
+        self.expect(')')?;
+
+        let arity_error = |expected: &str| {
+            ExpressionError::new(position, format!("{} takes {}", name, expected))
+        };
+
+        let value = match (name, args.as_slice()) {
+            ("abs", [x]) => x.abs(),
+            ("round", [x]) => x.round(),
+            ("round", [x, digits]) => {
+                let scale = 10f64.powi(digits.round() as i32);
+                (x * scale).round() / scale
+            }
+            ("floor", [x]) => x.floor(),
+            ("ceil", [x]) => x.ceil(),
+            ("sqrt", [x]) => x.sqrt(),
+            ("min", args) => args.iter().copied().fold(f64::INFINITY, f64::min),
+            ("max", args) => args.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            ("round", _) => return Err(arity_error("1 or 2 arguments")),
+            ("abs", _) | ("floor", _) | ("ceil", _) | ("sqrt", _) => {
+                return Err(arity_error("1 argument"))
+            }
+            _ => return Err(ExpressionError::new(position, format!("Unknown function '{}'", name))),
+        };
+
+        check_finite(value, position)
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), ExpressionError> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.pos += 1;
+                Ok(())
+            }
+            Some(c) => Err(ExpressionError::new(self.pos, format!("Expected '{}' but found '{}'", expected, c))),
+            None => Err(ExpressionError::new(self.pos, format!("Expected '{}'", expected))),
+        }
+    }
+}
+
+fn check_finite(value: f64, position: usize) -> Result<f64, ExpressionError> {
+    if value.is_finite() {
+        Ok(value)
+    } else {
+        Err(ExpressionError::new(position, "The result is not a number"))
+    }
+}
+
+#[cfg(test)]
+mod expression_tests {
+    use super::*;
+
+    #[test]
+    fn test_precedence_and_parentheses() {
+        assert_eq!(evaluate("1 + 2 * 3").unwrap(), 7.0);
+        assert_eq!(evaluate("(1 + 2) * 3").unwrap(), 9.0);
+        assert_eq!(evaluate("10 - 4 - 3").unwrap(), 3.0);
+        assert_eq!(evaluate("2 ^ 3 ^ 2").unwrap(), 512.0);
+        assert!((evaluate("(98.6 + 1.2) * 2").unwrap() - 199.6).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_unary_minus() {
+        assert_eq!(evaluate("-3 * -2").unwrap(), 6.0);
+        assert_eq!(evaluate("-2^2").unwrap(), -4.0);
+        assert_eq!(evaluate("2^-1").unwrap(), 0.5);
+        assert_eq!(evaluate("--5").unwrap(), 5.0);
+    }
+
+    #[test]
+    fn test_numbers() {
+        assert_eq!(evaluate("1e3").unwrap(), 1000.0);
+        assert_eq!(evaluate("2.5E-1").unwrap(), 0.25);
+        assert_eq!(evaluate(".5").unwrap(), 0.5);
+    }
+
+    #[test]
+    fn test_functions() {
+        assert_eq!(evaluate("abs(-40)").unwrap(), 40.0);
+        assert_eq!(evaluate("round(2.5)").unwrap(), 3.0);
+        assert_eq!(evaluate("round(1.23456, 2)").unwrap(), 1.23);
+        assert_eq!(evaluate("max(1, 5, 3) - min(4, 2)").unwrap(), 3.0);
+        assert_eq!(evaluate("sqrt(16) + floor(1.9) + ceil(1.1)").unwrap(), 7.0);
+    }
+
+    #[test]
+    fn test_error_positions() {
+        assert_eq!(evaluate("(98.6 + ) * 2").unwrap_err().position, 8);
+        assert_eq!(evaluate("(1 + 2").unwrap_err().position, 6);
+        assert_eq!(evaluate("1 + 2)").unwrap_err().position, 5);
+        assert_eq!(evaluate("2 * foo(1)").unwrap_err().position, 4);
+        assert_eq!(evaluate("1 / 0").unwrap_err().position, 2);
+        assert_eq!(evaluate("1..2").unwrap_err().position, 0);
+        assert_eq!(evaluate("").unwrap_err().position, 0);
+    }
+
+    #[test]
+    fn test_error_messages() {
+        assert_eq!(
+            evaluate("abs(1, 2)").unwrap_err(),
+            ExpressionError::new(0, "abs takes 1 argument")
+        );
+        assert_eq!(
+            evaluate("sqrt(-1)").unwrap_err().message,
+            "The result is not a number"
+        );
+        assert_eq!(evaluate("1e999 - 1").unwrap_err(), ExpressionError::new(0, "'1e999' is too big"));
+        assert_eq!(evaluate("(1 + ").unwrap_err().to_string(), "Expected a number at position 6!");
+        assert_eq!(evaluate("1 + ?").unwrap_err().pointer(), "    ^");
+    }
+
+    #[test]
+    fn test_overflow_is_an_error() {
+        assert_eq!(evaluate("1e308*10").unwrap_err().position, 5);
+        assert_eq!(evaluate("1e308 + 1e308").unwrap_err().position, 6);
+        assert_eq!(evaluate("-1e308 - 1e308").unwrap_err().position, 7);
+        assert_eq!(evaluate("1e308 / 0.1").unwrap_err().position, 6);
+        assert_eq!(
+            evaluate("1e308 * 10 - 1e308 * 10").unwrap_err().to_string(),
+            "The result is not a number at position 7!"
+        );
+    }
+
+    #[test]
+    fn test_prefix() {
+        assert_eq!(evaluate_prefix("(1 + 2) km/h").unwrap(), (3.0, "km/h"));
+        assert_eq!(evaluate_prefix("60mph").unwrap(), (60.0, "mph"));
+        assert_eq!(evaluate_prefix("1e3 m").unwrap(), (1000.0, "m"));
+        assert_eq!(evaluate_prefix("5 N*m").unwrap(), (5.0, "N*m"));
+        assert_eq!(evaluate_prefix("2 * 3 min").unwrap(), (6.0, "min"));
+    }
+}
@@ -0,0 +1,14 @@
+// The conversion logic for the converter lives here so it can be tested.
+// main.rs only deals with reading input and printing the results.
+
+pub mod batch;
+mod csv;
+pub mod expression;
+pub mod repl;
+pub mod temperature;
+pub mod units;
+
+pub use batch::{convert_csv, BatchConfig, BatchError, BatchReport};
+pub use expression::{evaluate, ExpressionError};
+pub use temperature::{Scale, Temperature, TemperatureError};
+pub use units::{Query, UnitError, UnitRegistry};
@@ -1,42 +1,103 @@
-use std::io::{self, Write};
+use std::env;
+use std::error::Error;
+use std::fs::File;
+use std::io::{self, BufReader};
+use std::path::PathBuf;
+use std::process;
+
+use rustyline::error::ReadlineError;
+use rustyline::DefaultEditor;
+
+use fahrenheit_converter::repl::{Repl, Response};
+use fahrenheit_converter::{convert_csv, BatchConfig, UnitRegistry};
 
 fn main() {
+    // With arguments the converter works through a CSV file rather than
+    // asking for numbers.
+    let batch_config = BatchConfig::new(env::args()).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {}", err);
+        process::exit(1);
+    });
+
+    if let Some(config) = batch_config {
+        match run_batch(&config) {
+            Ok(true) => return,
+            // Some rows failed. A different exit code to a fatal error lets
+            // scripts tell the two apart.
+            Ok(false) => process::exit(2),
+            Err(e) => {
+                eprintln!("Application Error: {}", e);
+                process::exit(1);
+            }
+        }
+    }
+
     println!("Welcome to the Fahrenheit Converter!");
+    println!("Type :help to see what it can do or q to quit.");
+
+    if let Err(e) = run_repl() {
+        println!("Application Error: {}", e);
+        process::exit(1);
+    }
+}
+
+// The history is kept in the home directory so it is shared between runs
+// wherever the converter is started from.
+fn history_path() -> PathBuf {
+    let home = env::var_os("HOME").map(PathBuf::from).unwrap_or_default();
+    home.join(".fahrenheit_converter_history")
+}
+
+// rustyline gives us line editing and up/down through the history, while
+// Repl decides what each line means.
+fn run_repl() -> Result<(), Box<dyn Error>> {
+    let history_path = history_path();
+    let mut repl = Repl::new(Repl::load_history(&history_path));
+
+    let mut editor = DefaultEditor::new()?;
+    for entry in repl.history() {
+        editor.add_history_entry(entry.as_str())?;
+    }
 
     loop {
-    
-        // String to store input value
-        let mut input = String::new();
-    
-        // Read input from cli
-        // Flush output buffer to output before new line
-        print!("Please enter the number you'd like to convert or enter q to quit: ");
-        io::stdout()
-            .flush()
-            .expect("Could not flush the output buffer!");
-        
-        // Read in number
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read line from terminal!");
-
-        if input.trim() == "q" || input.trim() == "Q" {
-            break;
+        let line = match editor.readline(&repl.prompt()) {
+            Ok(line) => line,
+            // Ctrl-C, Ctrl-D or the end of piped input
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e.into()),
+        };
+        editor.add_history_entry(line.as_str())?;
+
+        match repl.handle(&line) {
+            Response::Output(text) if text.is_empty() => {}
+            Response::Output(text) => println!("{}", text),
+            Response::Quit => break,
         }
+    }
 
-        let output: f32 = match input.trim().parse() {
-            Ok(num) => num,
-            Err(_) => {
-                println!("Could not parse number!");
-                continue;
-            }
-        };
+    if let Err(e) = repl.save_history(&history_path) {
+        println!("Could not save history to {}: {}", history_path.display(), e);
+    }
 
-        // Perform conversion
-        let output = (output - 32.0) * (5.0 / 9.0);
+    Ok(())
+}
+
+// The converted CSV goes to stdout (or --output) and the report to stderr so
+// they don't get mixed up when the output is piped somewhere.
+// Returns false if any rows failed.
+fn run_batch(config: &BatchConfig) -> Result<bool, Box<dyn Error>> {
+    let registry = UnitRegistry::default();
+    let mut input = BufReader::new(File::open(&config.input_path)?);
 
-        // Output result
-        println!("{}°F == {:.2}°C", input.trim(), output);
+    let report = match &config.output_path {
+        Some(path) => convert_csv(config, &registry, &mut input, &mut File::create(path)?)?,
+        None => convert_csv(config, &registry, &mut input, &mut io::stdout().lock())?,
+    };
 
+    for failure in &report.failures {
+        eprintln!("Line {}: {}", failure.line, failure.message);
     }
+    eprintln!("Converted {} rows, {} failed.", report.converted, report.failures.len());
+
+    Ok(report.failures.is_empty())
 }
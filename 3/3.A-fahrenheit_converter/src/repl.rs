@@ -0,0 +1,361 @@
+// The interactive side of the converter.
+// Lines starting with : are commands that change how numbers are converted:
+//
+//     :from UNIT       the unit for numbers typed without one (default F)
+//     :to UNIT         the unit to convert to, or just :to for every scale
+//     :precision N     how many decimal places to show
+//     :history         list what has been typed
+//     :help            list the commands
+//
+// Anything else is converted using the current settings, which are shown in
+// the prompt. Reading lines from the terminal is left to main.rs so that
+// everything here can be tested by feeding it strings.
+
+use std::fs;
+use std::path::Path;
+
+use crate::expression::{self, ExpressionError};
+use crate::units::is_query;
+use crate::{Query, Scale, Temperature, TemperatureError, UnitError, UnitRegistry};
+
+const MAX_HISTORY: usize = 500;
+
+const HELP: &str = "\
+Type a number to convert it, e.g. 100, 37.5C or (98.6 + 1.2) * 2.
+Other units can be converted with queries like '60 mph in m/s'.
+Commands:
+  :from UNIT       the unit for numbers typed without one (C, F, K, R or e.g. km)
+  :to UNIT         the unit to convert to, or just :to for every temperature scale
+  :precision N     how many decimal places to show
+  :history         list what has been typed
+  :help            show this message
+  q                quit";
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub from: String,
+    pub to: Option<String>,
+    pub precision: usize,
+}
+
+impl Default for Settings {
+    fn default() -> Settings {
+        Settings { from: String::from("F"), to: None, precision: 2 }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Response {
+    Output(String),
+    Quit,
+}
+
+pub struct Repl {
+    pub settings: Settings,
+    registry: UnitRegistry,
+    history: Vec<String>,
+}
+
+// The temperature scales can be written as just a letter in settings, but the
+// unit registry needs the longer names since C and F are taken.
+fn registry_name(unit: &str) -> String {
+    match Scale::from_suffix(unit) {
+        Some(Scale::Celsius) => String::from("degC"),
+        Some(Scale::Fahrenheit) => String::from("degF"),
+        Some(Scale::Kelvin) => String::from("K"),
+        Some(Scale::Rankine) => String::from("degR"),
+        None => unit.to_string(),
+    }
+}
+
+impl Repl {
+    pub fn new(history: Vec<String>) -> Repl {
+        Repl {
+            settings: Settings::default(),
+            registry: UnitRegistry::default(),
+            history,
+        }
+    }
+
+    /// Read a history file, one entry per line. A missing or unreadable
+    /// file just means there is no history yet.
+    pub fn load_history(path: &Path) -> Vec<String> {
+        fs::read_to_string(path)
+            .map(|contents| contents.lines().map(String::from).collect())
+            .unwrap_or_default()
+    }
+
+    /// Save the most recent history entries, one per line.
+    pub fn save_history(&self, path: &Path) -> std::io::Result<()> {
+        let start = self.history.len().saturating_sub(MAX_HISTORY);
+        let mut contents = self.history[start..].join("\n");
+        contents.push('\n');
+        fs::write(path, contents)
+    }
+
+    pub fn history(&self) -> &[String] {
+        &self.history
+    }
+
+    pub fn prompt(&self) -> String {
+        let to = self.settings.to.as_deref().unwrap_or("all");
+        format!("[{} -> {}, {} dp] > ", self.settings.from, to, self.settings.precision)
+    }
+
+    pub fn handle(&mut self, line: &str) -> Response {
+        let line = line.trim_end_matches(['\n', '\r']);
+        let trimmed = line.trim();
+
+        if trimmed.is_empty() {
+            return Response::Output(String::new());
+        }
+
+        if self.history.last().map(String::as_str) != Some(line) {
+            self.history.push(line.to_string());
+        }
+
+        if trimmed == "q" || trimmed == "Q" || trimmed == ":q" || trimmed == ":quit" {
+            return Response::Quit;
+        }
+
+        let output = match trimmed.strip_prefix(':') {
+            Some(command) => self.command(command),
+            None => self.convert(line).unwrap_or_else(|e| e),
+        };
+
+        Response::Output(output)
+    }
+
+    fn command(&mut self, command: &str) -> String {
+        let (name, argument) = match command.split_once(char::is_whitespace) {
+            Some((name, argument)) => (name, argument.trim()),
+            None => (command, ""),
+        };
+
+        match (name, argument) {
+            ("help", _) => String::from(HELP),
+            ("history", _) => self
+                .history
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| format!("{:>4}  {}", i + 1, entry))
+                .collect::<Vec<String>>()
+                .join("\n"),
+            ("from", "") => String::from("Usage: :from UNIT"),
+            ("from", unit) => self.set_from(unit),
+            ("to", "") => {
+                self.settings.to = None;
+                String::from("Converting to every temperature scale")
+            }
+            ("to", unit) => self.set_to(unit),
+            ("precision", digits) => match digits.parse::<usize>() {
+                Ok(digits) if digits <= 15 => {
+                    self.settings.precision = digits;
+                    format!("Showing {} decimal places", digits)
+                }
+                _ => String::from("Usage: :precision N, where N is 0 to 15"),
+            },
+            _ => format!("Unknown command ':{}', try :help", name),
+        }
+    }
+
+    fn set_from(&mut self, unit: &str) -> String {
+        if let Err(e) = self.registry.parse_unit(&registry_name(unit)) {
+            return e.to_string();
+        }
+
+        self.settings.from = unit.to_string();
+        let mut message = format!("Converting from {}", unit);
+
+        // A target that doesn't fit the new unit would make every
+        // conversion fail, so it is dropped.
+        if let Some(to) = &self.settings.to {
+            if self.check_units(unit, to).is_err() {
+                message.push_str(&format!(" (cleared :to {} as it doesn't match)", to));
+                self.settings.to = None;
+            }
+        }
+
+        message
+    }
+
+    fn set_to(&mut self, unit: &str) -> String {
+        match self.check_units(&self.settings.from, unit) {
+            Ok(()) => {
+                self.settings.to = Some(unit.to_string());
+                format!("Converting to {}", unit)
+            }
+            Err(e) => e.to_string(),
+        }
+    }
+
+    fn check_units(&self, from: &str, to: &str) -> Result<(), UnitError> {
+        // Only whether the conversion works matters, not the value.
+        self.registry
+            .convert(1.0, &registry_name(from), &registry_name(to))
+            .map(|_| ())
+    }
+
+    fn convert(&self, line: &str) -> Result<String, String> {
+        let precision = self.settings.precision;
+
+        if is_query(line) {
+            let query = Query::parse(line).map_err(|e| self.unit_error(e))?;
+            let result = query.run(&self.registry).map_err(|e| self.unit_error(e))?;
+            return Ok(format!("{} {} == {:.*} {}", query.value, query.from, precision, result, query.to));
+        }
+
+        let to_scale = match &self.settings.to {
+            None => None,
+            Some(to) => Scale::from_suffix(to),
+        };
+
+        // Temperatures keep their own parsing and the list of every scale.
+        if let Some(from_scale) = Scale::from_suffix(&self.settings.from) {
+            if self.settings.to.is_none() || to_scale.is_some() {
+                let temperature = Temperature::parse_with_default(line, from_scale).map_err(|e| match e {
+                    TemperatureError::Expression(e) => self.expression_error(&e),
+                    e => e.to_string(),
+                })?;
+
+                let scales = match to_scale {
+                    Some(scale) => vec![scale],
+                    None => Scale::ALL.to_vec(),
+                };
+                let conversions: Vec<String> = scales
+                    .iter()
+                    .map(|scale| format!("{:.*}", precision, temperature.to(*scale)))
+                    .collect();
+
+                return Ok(format!("{} == {}", temperature, conversions.join(" == ")));
+            }
+        }
+
+        let (value, unit) = expression::evaluate_prefix(line).map_err(|e| self.expression_error(&e))?;
+        let from = if unit.is_empty() { self.settings.from.as_str() } else { unit };
+        let to = self
+            .settings
+            .to
+            .as_deref()
+            .ok_or("Use :to UNIT to say what to convert to")?;
+
+        let result = self
+            .registry
+            .convert(value, &registry_name(from), &registry_name(to))
+            .map_err(|e| self.unit_error(e))?;
+
+        Ok(format!("{} {} == {:.*} {}", value, from, precision, result, to))
+    }
+
+    // The input is shown straight after the prompt, so the ^ has to be moved
+    // along by the width of the prompt to line up with it.
+    fn expression_error(&self, error: &ExpressionError) -> String {
+        let indent = " ".repeat(self.prompt().chars().count());
+        format!("{}{}\n{}", indent, error.pointer(), error)
+    }
+
+    fn unit_error(&self, error: UnitError) -> String {
+        match error {
+            UnitError::Expression(e) => self.expression_error(&e),
+            e => e.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod repl_tests {
+    use super::*;
+
+    fn output(repl: &mut Repl, line: &str) -> String {
+        match repl.handle(line) {
+            Response::Output(text) => text,
+            Response::Quit => panic!("Unexpected quit on '{}'", line),
+        }
+    }
+
+    #[test]
+    fn test_default_conversion() {
+        let mut repl = Repl::new(Vec::new());
+
+        assert_eq!(output(&mut repl, "212\n"), "212°F == 100.00°C == 212.00°F == 373.15K == 671.67°R");
+        assert_eq!(repl.prompt(), "[F -> all, 2 dp] > ");
+    }
+
+    #[test]
+    fn test_settings_change_conversion() {
+        let mut repl = Repl::new(Vec::new());
+
+        output(&mut repl, ":from C");
+        output(&mut repl, ":to K");
+        output(&mut repl, ":precision 3");
+
+        assert_eq!(repl.prompt(), "[C -> K, 3 dp] > ");
+        assert_eq!(output(&mut repl, "100"), "100°C == 373.150K");
+        assert_eq!(output(&mut repl, "32F"), "32°F == 273.150K");
+    }
+
+    #[test]
+    fn test_general_units() {
+        let mut repl = Repl::new(Vec::new());
+
+        output(&mut repl, ":from km");
+        assert_eq!(output(&mut repl, "5"), "Use :to UNIT to say what to convert to");
+
+        output(&mut repl, ":to m");
+        assert_eq!(output(&mut repl, "1.5"), "1.5 km == 1500.00 m");
+        assert_eq!(output(&mut repl, "2 mi"), "2 mi == 3218.69 m");
+        assert_eq!(output(&mut repl, "60 mph in km/h"), "60 mph == 96.56 km/h");
+    }
+
+    #[test]
+    fn test_bad_settings_are_rejected() {
+        let mut repl = Repl::new(Vec::new());
+
+        assert!(output(&mut repl, ":to kg").starts_with("Cannot convert"));
+        assert!(output(&mut repl, ":from furlongs").starts_with("Unknown unit"));
+        assert!(output(&mut repl, ":precision lots").starts_with("Usage"));
+        assert!(output(&mut repl, ":frobnicate").starts_with("Unknown command"));
+        assert_eq!(repl.settings, Settings::default());
+    }
+
+    #[test]
+    fn test_changing_from_clears_mismatched_to() {
+        let mut repl = Repl::new(Vec::new());
+
+        output(&mut repl, ":to C");
+        assert!(output(&mut repl, ":from km").contains("cleared"));
+        assert_eq!(repl.settings.to, None);
+    }
+
+    #[test]
+    fn test_history() {
+        let mut repl = Repl::new(vec![String::from("old")]);
+
+        output(&mut repl, "100");
+        output(&mut repl, "100");
+        output(&mut repl, "");
+
+        assert_eq!(repl.history(), &["old", "100"]);
+        assert_eq!(output(&mut repl, ":history"), "   1  old\n   2  100\n   3  :history");
+    }
+
+    #[test]
+    fn test_expression_error_lines_up_with_prompt() {
+        let mut repl = Repl::new(Vec::new());
+        let prompt_width = repl.prompt().chars().count();
+
+        // The number is missing straight after the +
+        let error = output(&mut repl, "1 + ");
+        let caret = error.find('^').unwrap();
+
+        assert_eq!(caret, prompt_width + 3);
+    }
+
+    #[test]
+    fn test_quit() {
+        let mut repl = Repl::new(Vec::new());
+
+        assert_eq!(repl.handle("q"), Response::Quit);
+        assert_eq!(repl.handle(":quit"), Response::Quit);
+    }
+}
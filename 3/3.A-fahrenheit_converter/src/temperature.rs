@@ -0,0 +1,252 @@
+// Temperatures in any of the four common scales.
+// Every conversion goes through Kelvin, so adding a scale only needs the
+// formulas to and from Kelvin rather than one for every other scale.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::expression::{self, ExpressionError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scale {
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+    Rankine,
+}
+
+impl Scale {
+    pub const ALL: [Scale; 4] = [Scale::Celsius, Scale::Fahrenheit, Scale::Kelvin, Scale::Rankine];
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Scale::Celsius => "°C",
+            Scale::Fahrenheit => "°F",
+            Scale::Kelvin => "K",
+            Scale::Rankine => "°R",
+        }
+    }
+
+    pub fn from_suffix(suffix: &str) -> Option<Scale> {
+        match suffix.trim().trim_start_matches('°').to_uppercase().as_str() {
+            "C" => Some(Scale::Celsius),
+            "F" => Some(Scale::Fahrenheit),
+            "K" => Some(Scale::Kelvin),
+            "R" => Some(Scale::Rankine),
+            _ => None,
+        }
+    }
+
+    fn kelvin_from(self, value: f64) -> f64 {
+        match self {
+            Scale::Celsius => value + 273.15,
+            Scale::Fahrenheit => (value + 459.67) * (5.0 / 9.0),
+            Scale::Kelvin => value,
+            Scale::Rankine => value * (5.0 / 9.0),
+        }
+    }
+
+    fn value_from_kelvin(self, kelvin: f64) -> f64 {
+        match self {
+            Scale::Celsius => kelvin - 273.15,
+            Scale::Fahrenheit => kelvin * (9.0 / 5.0) - 459.67,
+            Scale::Kelvin => kelvin,
+            Scale::Rankine => kelvin * (9.0 / 5.0),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum TemperatureError {
+    Empty,
+    InvalidNumber(String),
+    Expression(ExpressionError),
+    UnknownUnit(String),
+    BelowAbsoluteZero,
+}
+
+impl fmt::Display for TemperatureError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TemperatureError::Empty => write!(f, "Nothing to convert!"),
+            TemperatureError::InvalidNumber(number) => write!(f, "Could not parse number '{}'!", number),
+            TemperatureError::Expression(e) => write!(f, "{}", e),
+            TemperatureError::UnknownUnit(unit) => {
+                write!(f, "Unknown unit '{}', use C, F, K or R!", unit)
+            }
+            TemperatureError::BelowAbsoluteZero => write!(f, "That's colder than absolute zero!"),
+        }
+    }
+}
+
+impl Error for TemperatureError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Temperature {
+    pub value: f64,
+    pub scale: Scale,
+}
+
+impl Temperature {
+    /// Temperatures below absolute zero don't exist so they can't be made.
+    pub fn new(value: f64, scale: Scale) -> Result<Temperature, TemperatureError> {
+        // A tiny tolerance so e.g. -459.67°F isn't rejected because of
+        // rounding on the way to Kelvin.
+        if scale.kelvin_from(value) < -1e-9 {
+            return Err(TemperatureError::BelowAbsoluteZero);
+        }
+        Ok(Temperature { value, scale })
+    }
+
+    /// Parse input such as "100C", "212 F", "-40°F" or "300K".
+    /// A number on its own is taken to be in Fahrenheit, which is all this
+    /// converter used to accept.
+    /// The number can also be an expression such as "(20 + 5) * 2C", see
+    /// expression.rs. Error positions count from the start of the input.
+    pub fn parse(input: &str) -> Result<Temperature, TemperatureError> {
+        Temperature::parse_with_default(input, Scale::Fahrenheit)
+    }
+
+    /// The same as parse but with a different scale for numbers that don't
+    /// say which one they are in.
+    pub fn parse_with_default(input: &str, default: Scale) -> Result<Temperature, TemperatureError> {
+        let leading = input.chars().take_while(|c| c.is_whitespace()).count();
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(TemperatureError::Empty);
+        }
+
+        // Expressions can't end in a letter (functions end in a bracket) so
+        // any letters on the end must be the unit.
+        let split = input
+            .trim_end_matches(|c: char| c.is_alphabetic() || c == '°')
+            .len();
+        let (number, suffix) = input.split_at(split);
+
+        let scale = if suffix.is_empty() {
+            default
+        } else {
+            Scale::from_suffix(suffix)
+                .ok_or_else(|| TemperatureError::UnknownUnit(suffix.to_string()))?
+        };
+
+        let number = number.trim_end();
+        let value = match number.parse::<f64>() {
+            Ok(value) if value.is_finite() => value,
+            // Only hand it to the expression parser if it looks like an
+            // expression, so a typo like "1.2.3" is still just a bad number.
+            _ if number.contains(|c: char| "+-*/^(),".contains(c) || c.is_alphabetic()) => {
+                expression::evaluate(number).map_err(|mut e| {
+                    e.position += leading;
+                    TemperatureError::Expression(e)
+                })?
+            }
+            _ => return Err(TemperatureError::InvalidNumber(number.to_string())),
+        };
+
+        Temperature::new(value, scale)
+    }
+
+    pub fn to(&self, scale: Scale) -> Temperature {
+        Temperature {
+            value: scale.value_from_kelvin(self.scale.kelvin_from(self.value)),
+            scale,
+        }
+    }
+}
+
+impl fmt::Display for Temperature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match f.precision() {
+            Some(precision) => write!(f, "{:.*}{}", precision, self.value, self.scale.symbol()),
+            None => write!(f, "{}{}", self.value, self.scale.symbol()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod temperature_tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn test_parse_suffixes() {
+        assert_eq!(Temperature::parse("100C").unwrap(), Temperature { value: 100.0, scale: Scale::Celsius });
+        assert_eq!(Temperature::parse(" 212 f ").unwrap().scale, Scale::Fahrenheit);
+        assert_eq!(Temperature::parse("-40°F").unwrap().value, -40.0);
+        assert_eq!(Temperature::parse("300K").unwrap().scale, Scale::Kelvin);
+        assert_eq!(Temperature::parse("500R").unwrap().scale, Scale::Rankine);
+        assert_eq!(Temperature::parse("98.6").unwrap().scale, Scale::Fahrenheit);
+    }
+
+    #[test]
+    fn test_parse_with_default() {
+        assert_eq!(Temperature::parse_with_default("100", Scale::Celsius).unwrap().scale, Scale::Celsius);
+        assert_eq!(Temperature::parse_with_default("100F", Scale::Celsius).unwrap().scale, Scale::Fahrenheit);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(Temperature::parse(""), Err(TemperatureError::Empty));
+        assert_eq!(Temperature::parse("12X"), Err(TemperatureError::UnknownUnit(String::from("X"))));
+        assert_eq!(Temperature::parse("hot"), Err(TemperatureError::UnknownUnit(String::from("hot"))));
+        assert_eq!(Temperature::parse("1.2.3C"), Err(TemperatureError::InvalidNumber(String::from("1.2.3"))));
+        assert_eq!(Temperature::parse("-1K"), Err(TemperatureError::BelowAbsoluteZero));
+        assert_eq!(Temperature::parse("-274C"), Err(TemperatureError::BelowAbsoluteZero));
+    }
+
+    #[test]
+    fn test_expressions() {
+        let temperature = Temperature::parse("(98.6 + 1.2) * 2").unwrap();
+        assert!((temperature.value - 199.6).abs() < 1e-9);
+        assert_eq!(temperature.scale, Scale::Fahrenheit);
+
+        assert_eq!(Temperature::parse("abs(-40)C").unwrap().value, 40.0);
+        assert_eq!(Temperature::parse("round(36.6) K").unwrap().value, 37.0);
+    }
+
+    #[test]
+    fn test_expression_error_position() {
+        match Temperature::parse("  (98.6 + ) * 2") {
+            Err(TemperatureError::Expression(e)) => assert_eq!(e.position, 10),
+            other => panic!("Expected an expression error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_absolute_zero_is_allowed() {
+        assert!(Temperature::parse("-459.67F").is_ok());
+        assert!(Temperature::parse("-273.15C").is_ok());
+        assert!(Temperature::parse("0R").is_ok());
+    }
+
+    #[test]
+    fn test_known_conversions() {
+        let boiling = Temperature::parse("100C").unwrap();
+
+        assert_close(boiling.to(Scale::Fahrenheit).value, 212.0);
+        assert_close(boiling.to(Scale::Kelvin).value, 373.15);
+        assert_close(boiling.to(Scale::Rankine).value, 671.67);
+        assert_close(Temperature::parse("-40F").unwrap().to(Scale::Celsius).value, -40.0);
+    }
+
+    #[test]
+    fn test_round_trips() {
+        for from in Scale::ALL.iter() {
+            for to in Scale::ALL.iter() {
+                let start = Temperature::new(500.0, *from).unwrap();
+                assert_close(start.to(*to).to(*from).value, 500.0);
+            }
+        }
+    }
+
+    #[test]
+    fn test_display_precision() {
+        let temperature = Temperature::parse("36.666C").unwrap();
+
+        assert_eq!(format!("{:.2}", temperature), "36.67°C");
+    }
+}
@@ -0,0 +1,275 @@
+// A general unit conversion engine. Temperature is just one kind of unit;
+// this handles lengths, masses, volumes, pressures, speeds and anything that
+// can be built from them.
+//
+// Queries look like "60 mph in m/s" or "3 N*m to J". Units can be combined
+// with * and / and raised to whole powers with ^, e.g. "kg*m/s^2".
+// Before converting, the dimensions of the two units are compared so that
+// nonsense such as "3 m in kg" is rejected rather than given a number.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::expression::{self, ExpressionError};
+
+mod dimension;
+mod registry;
+
+pub use dimension::Dimension;
+pub use registry::{Unit, UnitRegistry};
+
+#[derive(Debug, PartialEq)]
+pub enum UnitError {
+    UnknownUnit(String),
+    InvalidUnit(String),
+    OffsetInCompound(String),
+    PowerOverflow(String),
+    IncompatibleDimensions { from: String, to: String },
+    InvalidQuery(String),
+    Expression(ExpressionError),
+    BelowAbsoluteZero,
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnitError::UnknownUnit(unit) => write!(f, "Unknown unit '{}'!", unit),
+            UnitError::InvalidUnit(unit) => write!(f, "Could not understand the unit '{}'!", unit),
+            UnitError::OffsetInCompound(unit) => write!(
+                f,
+                "'{}' has a different zero point so it can't be part of a compound unit!",
+                unit,
+            ),
+            UnitError::PowerOverflow(unit) => write!(f, "The powers in '{}' are too large!", unit),
+            UnitError::IncompatibleDimensions { from, to } => {
+                write!(f, "Cannot convert {} to {}!", from, to)
+            }
+            UnitError::InvalidQuery(query) => {
+                write!(f, "Could not understand '{}', try something like '60 mph in m/s'!", query)
+            }
+            UnitError::Expression(e) => write!(f, "{}", e),
+            UnitError::BelowAbsoluteZero => write!(f, "That's colder than absolute zero!"),
+        }
+    }
+}
+
+impl Error for UnitError {}
+
+impl UnitRegistry {
+    /// Parse a unit written as names joined by * and /, each with an
+    /// optional ^power, e.g. "km/h", "N*m" or "kg*m/s^2".
+    /// Everything after a / is divided, so "m/s/s" is the same as "m/s^2".
+    pub fn parse_unit(&self, text: &str) -> Result<Unit, UnitError> {
+        let text: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        if text.is_empty() {
+            return Err(UnitError::InvalidUnit(text));
+        }
+
+        let mut terms = Vec::new();
+        let mut dividing = false;
+        let mut start = 0;
+
+        for (i, c) in text.char_indices().chain(std::iter::once((text.len(), '*'))) {
+            if c == '*' || c == '/' || c == '·' {
+                terms.push((&text[start..i], dividing));
+                dividing = dividing || c == '/';
+                start = i + c.len_utf8();
+            }
+        }
+
+        // A unit with an offset, like °C, only makes sense on its own.
+        if let [(term, false)] = terms.as_slice() {
+            if !term.contains('^') {
+                return self.lookup(term);
+            }
+        }
+
+        let mut unit = Unit::new(1.0, Dimension::NONE);
+        for (term, divide) in terms {
+            let (name, power) = match term.split_once('^') {
+                Some((name, power)) => {
+                    let power: i8 = power
+                        .parse()
+                        .map_err(|_| UnitError::InvalidUnit(text.clone()))?;
+                    (name, power)
+                }
+                None => (term, 1),
+            };
+
+            if name.is_empty() {
+                return Err(UnitError::InvalidUnit(text.clone()));
+            }
+
+            let base = self.lookup(name)?;
+            if base.offset != 0.0 {
+                return Err(UnitError::OffsetInCompound(name.to_string()));
+            }
+
+            let overflow = || UnitError::PowerOverflow(text.clone());
+            let power = if divide { power.checked_neg().ok_or_else(overflow)? } else { power };
+            unit = base
+                .checked_powi(power)
+                .and_then(|power| unit.checked_mul(&power))
+                .ok_or_else(overflow)?;
+        }
+
+        Ok(unit)
+    }
+
+    pub fn convert(&self, value: f64, from: &str, to: &str) -> Result<f64, UnitError> {
+        let from_unit = self.parse_unit(from)?;
+        let to_unit = self.parse_unit(to)?;
+
+        if from_unit.dimension != to_unit.dimension {
+            return Err(UnitError::IncompatibleDimensions {
+                from: format!("{} ({})", from, from_unit.dimension),
+                to: format!("{} ({})", to, to_unit.dimension),
+            });
+        }
+
+        // As with Temperature, a temperature below absolute zero is an error
+        // rather than something to convert.
+        let si = from_unit.to_si(value);
+        if from_unit.dimension == Dimension::TEMPERATURE && si < -1e-9 {
+            return Err(UnitError::BelowAbsoluteZero);
+        }
+
+        Ok(to_unit.from_si(si))
+    }
+}
+
+/// A query such as "60 mph in m/s", split into its parts.
+#[derive(Debug, PartialEq)]
+pub struct Query {
+    pub value: f64,
+    pub from: String,
+    pub to: String,
+}
+
+impl Query {
+    /// Split a query at its last " in " or " to ".
+    /// The number and the first unit don't need a space between them, so
+    /// "100km in mi" works too, and the number can be an expression such as
+    /// "(1 + 2) * 3 km in mi".
+    pub fn parse(input: &str) -> Result<Query, UnitError> {
+        let leading = input.chars().take_while(|c| c.is_whitespace()).count();
+        let input = input.trim();
+        let invalid = || UnitError::InvalidQuery(input.to_string());
+
+        let (left, to) = [" in ", " to "]
+            .iter()
+            .filter_map(|separator| input.rfind(separator).map(|i| (i, separator.len())))
+            .max()
+            .map(|(i, len)| (&input[..i], &input[i + len..]))
+            .ok_or_else(invalid)?;
+
+        let (value, from) = expression::evaluate_prefix(left).map_err(|mut e| {
+            e.position += leading;
+            UnitError::Expression(e)
+        })?;
+        let to = to.trim();
+
+        if from.is_empty() || to.is_empty() {
+            return Err(invalid());
+        }
+
+        Ok(Query { value, from: from.to_string(), to: to.to_string() })
+    }
+
+    pub fn run(&self, registry: &UnitRegistry) -> Result<f64, UnitError> {
+        registry.convert(self.value, &self.from, &self.to)
+    }
+}
+
+/// Does the input look like a unit query rather than a plain temperature?
+pub fn is_query(input: &str) -> bool {
+    input.contains(" in ") || input.contains(" to ")
+}
+
+#[cfg(test)]
+mod units_tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9 * b.abs().max(1.0), "{} != {}", a, b);
+    }
+
+    fn convert(query: &str) -> Result<f64, UnitError> {
+        Query::parse(query)?.run(&UnitRegistry::default())
+    }
+
+    #[test]
+    fn test_simple_conversions() {
+        assert_close(convert("1 mi in km").unwrap(), 1.609344);
+        assert_close(convert("2.2 lb to kg").unwrap(), 0.997903214);
+        assert_close(convert("1 atm in psi").unwrap(), 14.695948775);
+        assert_close(convert("1 gal in L").unwrap(), 3.785411784);
+    }
+
+    #[test]
+    fn test_compound_units() {
+        assert_close(convert("60 mph in m/s").unwrap(), 26.8224);
+        assert_close(convert("36 km/h in m/s").unwrap(), 10.0);
+        assert_close(convert("5 N*m in J").unwrap(), 5.0);
+        assert_close(convert("1 N in kg*m/s^2").unwrap(), 1.0);
+        assert_close(convert("9.81 m/s/s in m/s^2").unwrap(), 9.81);
+        assert_close(convert("1 m^3 in L").unwrap(), 1000.0);
+    }
+
+    #[test]
+    fn test_temperatures() {
+        assert_close(convert("100 degC in degF").unwrap(), 212.0);
+        assert_close(convert("0 K in °F").unwrap(), -459.67);
+        assert_close(convert("491.67 degR in degC").unwrap(), 0.0);
+        assert_eq!(convert("-300 degC in K"), Err(UnitError::BelowAbsoluteZero));
+    }
+
+    #[test]
+    fn test_dimension_check() {
+        assert_eq!(
+            convert("3 m in kg"),
+            Err(UnitError::IncompatibleDimensions {
+                from: String::from("m (m)"),
+                to: String::from("kg (kg)"),
+            })
+        );
+        assert!(convert("1 km/h in m").is_err());
+    }
+
+    #[test]
+    fn test_offset_units_alone_only() {
+        assert_eq!(
+            convert("1 degC/s in K/s"),
+            Err(UnitError::OffsetInCompound(String::from("degC")))
+        );
+    }
+
+    #[test]
+    fn test_query_parsing() {
+        assert_eq!(
+            Query::parse("100km in mi").unwrap(),
+            Query { value: 100.0, from: String::from("km"), to: String::from("mi") }
+        );
+        assert_eq!(Query::parse("1e3 m to km").unwrap().value, 1000.0);
+        assert!(Query::parse("60 mph").is_err());
+        assert!(Query::parse("mph in m/s").is_err());
+        assert_eq!(Query::parse("(1 + 2) * 3 km in mi").unwrap().value, 9.0);
+        assert!(Query::parse("60 in m/s").is_err());
+    }
+
+    #[test]
+    fn test_bad_units() {
+        let registry = UnitRegistry::default();
+
+        assert_eq!(registry.parse_unit("furlong"), Err(UnitError::UnknownUnit(String::from("furlong"))));
+        assert!(registry.parse_unit("m/").is_err());
+        assert!(registry.parse_unit("m^x").is_err());
+    }
+
+    #[test]
+    fn test_power_overflow() {
+        assert_eq!(convert("1 N^100 in J"), Err(UnitError::PowerOverflow(String::from("N^100"))));
+        assert_eq!(convert("1 m/m^-128 in m"), Err(UnitError::PowerOverflow(String::from("m/m^-128"))));
+        assert_eq!(convert("1 m^127*m in m"), Err(UnitError::PowerOverflow(String::from("m^127*m"))));
+    }
+}
@@ -0,0 +1,114 @@
+// A dimension is what kind of thing a unit measures, stored as the power of
+// each SI base quantity. Speed is length^1 time^-1, force is
+// mass^1 length^1 time^-2, and so on.
+// Two units can only be converted between if their dimensions are equal.
+
+use std::fmt;
+use std::ops::{Div, Mul};
+
+const BASE_SYMBOLS: [&str; 5] = ["kg", "m", "s", "K", "A"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Dimension {
+    powers: [i8; 5],
+}
+
+impl Dimension {
+    pub const NONE: Dimension = Dimension { powers: [0; 5] };
+    pub const MASS: Dimension = Dimension { powers: [1, 0, 0, 0, 0] };
+    pub const LENGTH: Dimension = Dimension { powers: [0, 1, 0, 0, 0] };
+    pub const TIME: Dimension = Dimension { powers: [0, 0, 1, 0, 0] };
+    pub const TEMPERATURE: Dimension = Dimension { powers: [0, 0, 0, 1, 0] };
+    pub const CURRENT: Dimension = Dimension { powers: [0, 0, 0, 0, 1] };
+
+    pub fn powi(self, exponent: i8) -> Dimension {
+        self.checked_powi(exponent).expect("dimension power overflowed")
+    }
+
+    // The powers are only an i8, so units typed in by the user such as
+    // m^100*m^100 have to be checked rather than allowed to overflow.
+    pub fn checked_powi(self, exponent: i8) -> Option<Dimension> {
+        let mut powers = self.powers;
+        for power in powers.iter_mut() {
+            *power = power.checked_mul(exponent)?;
+        }
+        Some(Dimension { powers })
+    }
+
+    // Multiplying two quantities adds the powers of their dimensions.
+    pub fn checked_mul(self, other: Dimension) -> Option<Dimension> {
+        let mut powers = self.powers;
+        for (power, other) in powers.iter_mut().zip(other.powers.iter()) {
+            *power = power.checked_add(*other)?;
+        }
+        Some(Dimension { powers })
+    }
+}
+
+impl Mul for Dimension {
+    type Output = Dimension;
+
+    fn mul(self, other: Dimension) -> Dimension {
+        self.checked_mul(other).expect("dimension power overflowed")
+    }
+}
+
+impl Div for Dimension {
+    type Output = Dimension;
+
+    fn div(self, other: Dimension) -> Dimension {
+        self * other.powi(-1)
+    }
+}
+
+// Written in SI base units, e.g. kg*m*s^-2 for a force.
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let parts: Vec<String> = self
+            .powers
+            .iter()
+            .zip(BASE_SYMBOLS.iter())
+            .filter(|(power, _)| **power != 0)
+            .map(|(power, symbol)| match power {
+                1 => symbol.to_string(),
+                _ => format!("{}^{}", symbol, power),
+            })
+            .collect();
+
+        if parts.is_empty() {
+            write!(f, "dimensionless")
+        } else {
+            write!(f, "{}", parts.join("*"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod dimension_tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let speed = Dimension::LENGTH / Dimension::TIME;
+        let force = Dimension::MASS * Dimension::LENGTH / Dimension::TIME.powi(2);
+
+        assert_eq!(speed * Dimension::TIME, Dimension::LENGTH);
+        assert_eq!(force / Dimension::MASS, speed / Dimension::TIME);
+        assert_eq!(speed / speed, Dimension::NONE);
+    }
+
+    #[test]
+    fn test_display() {
+        let force = Dimension::MASS * Dimension::LENGTH / Dimension::TIME.powi(2);
+
+        assert_eq!(force.to_string(), "kg*m*s^-2");
+        assert_eq!(Dimension::NONE.to_string(), "dimensionless");
+    }
+
+    #[test]
+    fn test_overflow() {
+        assert_eq!(Dimension::LENGTH.checked_powi(100).unwrap().checked_powi(2), None);
+        assert_eq!(Dimension::LENGTH.checked_powi(127).unwrap().checked_mul(Dimension::LENGTH), None);
+        assert_eq!(Dimension::LENGTH.checked_powi(-128).unwrap().checked_powi(-1), None);
+    }
+}
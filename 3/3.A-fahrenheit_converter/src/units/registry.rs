@@ -0,0 +1,217 @@
+// The table of every unit the converter knows about.
+// Each unit is stored as how to turn a value in that unit into the matching
+// SI unit: si = value * factor + offset. Only the temperature scales with a
+// different zero point (°C, °F) have an offset.
+//
+// Units marked as prefixable can also be written with an SI prefix, so "km"
+// and "mg" don't need their own entries.
+
+use std::collections::HashMap;
+
+use super::{Dimension, UnitError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Unit {
+    pub factor: f64,
+    pub offset: f64,
+    pub dimension: Dimension,
+}
+
+impl Unit {
+    pub fn new(factor: f64, dimension: Dimension) -> Unit {
+        Unit { factor, offset: 0.0, dimension }
+    }
+
+    pub fn to_si(&self, value: f64) -> f64 {
+        value * self.factor + self.offset
+    }
+
+    pub fn from_si(&self, value: f64) -> f64 {
+        (value - self.offset) / self.factor
+    }
+
+    // None if the powers of the dimension get too large, see Dimension.
+    pub fn checked_powi(&self, exponent: i8) -> Option<Unit> {
+        Some(Unit::new(self.factor.powi(exponent as i32), self.dimension.checked_powi(exponent)?))
+    }
+
+    pub fn checked_mul(&self, other: &Unit) -> Option<Unit> {
+        Some(Unit::new(self.factor * other.factor, self.dimension.checked_mul(other.dimension)?))
+    }
+}
+
+const PREFIXES: [(&str, f64); 20] = [
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("da", 1e1),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("µ", 1e-6),
+    ("u", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+    ("a", 1e-18),
+    ("z", 1e-21),
+];
+
+struct Entry {
+    unit: Unit,
+    prefixable: bool,
+}
+
+pub struct UnitRegistry {
+    units: HashMap<String, Entry>,
+}
+
+impl UnitRegistry {
+    pub fn new() -> UnitRegistry {
+        UnitRegistry { units: HashMap::new() }
+    }
+
+    pub fn add(&mut self, names: &[&str], unit: Unit, prefixable: bool) {
+        for name in names {
+            self.units.insert(name.to_string(), Entry { unit, prefixable });
+        }
+    }
+
+    /// Find a single unit by name, with or without an SI prefix.
+    /// A name that is registered exactly always wins, so "min" is minutes
+    /// rather than milli-inches and "h" is hours rather than hecto-nothing.
+    pub fn lookup(&self, name: &str) -> Result<Unit, UnitError> {
+        if let Some(entry) = self.units.get(name) {
+            return Ok(entry.unit);
+        }
+
+        for (prefix, scale) in PREFIXES.iter() {
+            let rest = match name.strip_prefix(prefix) {
+                Some(rest) => rest,
+                None => continue,
+            };
+
+            if let Some(entry) = self.units.get(rest) {
+                if entry.prefixable && entry.unit.offset == 0.0 {
+                    return Ok(Unit::new(entry.unit.factor * scale, entry.unit.dimension));
+                }
+            }
+        }
+
+        Err(UnitError::UnknownUnit(name.to_string()))
+    }
+}
+
+impl Default for UnitRegistry {
+    /// The units our team uses day to day: lengths, masses, volumes,
+    /// pressures, speeds and temperatures, plus what's needed to build them.
+    fn default() -> UnitRegistry {
+        let mut registry = UnitRegistry::new();
+
+        let length = Dimension::LENGTH;
+        let mass = Dimension::MASS;
+        let time = Dimension::TIME;
+        let area = length.powi(2);
+        let volume = length.powi(3);
+        let speed = length / time;
+        let force = mass * length / time.powi(2);
+        let energy = force * length;
+        let pressure = force / area;
+
+        // SI base and derived units. The gram is the prefixable mass unit
+        // even though the kilogram is the SI one.
+        registry.add(&["m"], Unit::new(1.0, length), true);
+        registry.add(&["g"], Unit::new(1e-3, mass), true);
+        registry.add(&["s"], Unit::new(1.0, time), true);
+        registry.add(&["A"], Unit::new(1.0, Dimension::CURRENT), true);
+        registry.add(&["K"], Unit::new(1.0, Dimension::TEMPERATURE), true);
+        registry.add(&["N"], Unit::new(1.0, force), true);
+        registry.add(&["J"], Unit::new(1.0, energy), true);
+        registry.add(&["W"], Unit::new(1.0, energy / time), true);
+        registry.add(&["Pa"], Unit::new(1.0, pressure), true);
+        registry.add(&["L", "l"], Unit::new(1e-3, volume), true);
+        registry.add(&["bar"], Unit::new(1e5, pressure), true);
+        registry.add(&["t", "tonne"], Unit::new(1e3, mass), false);
+
+        // Time
+        registry.add(&["min"], Unit::new(60.0, time), false);
+        registry.add(&["h", "hr"], Unit::new(3600.0, time), false);
+        registry.add(&["day"], Unit::new(86400.0, time), false);
+
+        // Imperial and US customary
+        registry.add(&["in"], Unit::new(0.0254, length), false);
+        registry.add(&["ft"], Unit::new(0.3048, length), false);
+        registry.add(&["yd"], Unit::new(0.9144, length), false);
+        registry.add(&["mi"], Unit::new(1609.344, length), false);
+        registry.add(&["nmi"], Unit::new(1852.0, length), false);
+        registry.add(&["oz"], Unit::new(0.028349523125, mass), false);
+        registry.add(&["lb"], Unit::new(0.45359237, mass), false);
+        registry.add(&["st"], Unit::new(6.35029318, mass), false);
+        registry.add(&["gal"], Unit::new(3.785411784e-3, volume), false);
+        registry.add(&["pt"], Unit::new(4.73176473e-4, volume), false);
+        registry.add(&["lbf"], Unit::new(4.4482216152605, force), false);
+        registry.add(&["psi"], Unit::new(6894.757293168, pressure), false);
+        registry.add(&["atm"], Unit::new(101325.0, pressure), false);
+        registry.add(&["mmHg"], Unit::new(133.322387415, pressure), false);
+        registry.add(&["mph"], Unit::new(0.44704, speed), false);
+        registry.add(&["kn", "kt"], Unit::new(1852.0 / 3600.0, speed), false);
+        registry.add(&["cal"], Unit::new(4.184, energy), true);
+
+        // Temperatures. C and F are left free for coulombs and farads so the
+        // scales are written degC/°C and degF/°F.
+        let temperature = Dimension::TEMPERATURE;
+        registry.add(
+            &["degC", "°C"],
+            Unit { factor: 1.0, offset: 273.15, dimension: temperature },
+            false,
+        );
+        registry.add(
+            &["degF", "°F"],
+            Unit { factor: 5.0 / 9.0, offset: 459.67 * 5.0 / 9.0, dimension: temperature },
+            false,
+        );
+        registry.add(&["degR", "°R"], Unit::new(5.0 / 9.0, temperature), false);
+
+        registry
+    }
+}
+
+#[cfg(test)]
+mod registry_tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_names_beat_prefixes() {
+        let registry = UnitRegistry::default();
+
+        assert_eq!(registry.lookup("min").unwrap().factor, 60.0);
+        assert_eq!(registry.lookup("h").unwrap().factor, 3600.0);
+        assert_eq!(registry.lookup("mi").unwrap().factor, 1609.344);
+    }
+
+    #[test]
+    fn test_prefixes() {
+        let registry = UnitRegistry::default();
+
+        assert_eq!(registry.lookup("km").unwrap(), Unit::new(1000.0, Dimension::LENGTH));
+        assert_eq!(registry.lookup("kg").unwrap(), Unit::new(1.0, Dimension::MASS));
+        assert_eq!(registry.lookup("µs").unwrap().factor, 1e-6);
+        assert_eq!(registry.lookup("us").unwrap().factor, 1e-6);
+        assert_eq!(registry.lookup("dam").unwrap().factor, 10.0);
+    }
+
+    #[test]
+    fn test_prefixes_only_on_prefixable_units() {
+        let registry = UnitRegistry::default();
+
+        assert!(registry.lookup("kft").is_err());
+        assert!(registry.lookup("kdegC").is_err());
+        assert!(registry.lookup("furlong").is_err());
+    }
+}
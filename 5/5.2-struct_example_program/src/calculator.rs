@@ -0,0 +1,299 @@
+// The shape calculator: read shapes from a file and answer questions about
+// them.
+//
+// Shape files have one shape per line, with fields separated by commas
+// and/or spaces, so both CSV and hand written text work:
+//
+//     # kind, name, x, y, width, height
+//     rect, door, 0, 0, 3, 7
+//     rect window 4 2 2 2
+//     rect, table, 5, 3
+//
+// A rect with only a width and height is put at (0, 0). Blank lines and
+// lines starting with # are skipped.
+
+use std::error::Error;
+use std::fmt;
+use std::fs;
+
+use crate::{Rect, Shape};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Query {
+    TotalArea,
+    Largest,
+    Containment,
+    Overlaps,
+}
+
+impl Query {
+    pub const ALL: [Query; 4] = [Query::TotalArea, Query::Largest, Query::Containment, Query::Overlaps];
+
+    pub fn parse(text: &str) -> Option<Query> {
+        match text {
+            "total-area" | "area" => Some(Query::TotalArea),
+            "largest" => Some(Query::Largest),
+            "containment" | "contains" => Some(Query::Containment),
+            "overlaps" => Some(Query::Overlaps),
+            _ => None,
+        }
+    }
+}
+
+pub struct Config {
+    pub filepath: String,
+    pub queries: Vec<Query>,
+}
+
+impl Config {
+    // Expects the file and then any number of queries. With no queries
+    // every one of them is answered.
+    pub fn new<I>(args: I) -> Result<Config, &'static str>
+    where I: Iterator<Item = String>
+    {
+        let mut args = args.skip(1);
+
+        let filepath = match args.next() {
+            Some(arg) => arg,
+            None => return Err("Error, not enough arguments!"),
+        };
+
+        let mut queries = args
+            .map(|arg| Query::parse(&arg).ok_or("Unknown query, use total-area, largest, containment or overlaps!"))
+            .collect::<Result<Vec<Query>, &'static str>>()?;
+
+        if queries.is_empty() {
+            queries = Query::ALL.to_vec();
+        }
+
+        Ok(Config { filepath, queries })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ParseError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Line {}: {}", self.line, self.message)
+    }
+}
+
+impl Error for ParseError {}
+
+#[derive(Debug)]
+pub struct NamedShape {
+    pub name: String,
+    pub shape: Box<dyn Shape>,
+}
+
+pub fn parse_shapes(contents: &str) -> Result<Vec<NamedShape>, ParseError> {
+    let mut shapes = Vec::new();
+
+    for (i, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let error = |message: String| ParseError { line: i + 1, message };
+        let fields: Vec<&str> = line
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|field| !field.is_empty())
+            .collect();
+
+        let (kind, name, numbers) = match fields.as_slice() {
+            [kind, name, numbers @ ..] => (*kind, *name, numbers),
+            _ => return Err(error(String::from("Expected a kind and a name"))),
+        };
+
+        let numbers = numbers
+            .iter()
+            .map(|n| n.parse::<i32>().map_err(|_| error(format!("'{}' is not a whole number", n))))
+            .collect::<Result<Vec<i32>, ParseError>>()?;
+
+        let shape: Box<dyn Shape> = match (kind, numbers.as_slice()) {
+            // Rect::build would panic on a negative size, so both go through try_new.
+            ("rect", [width, height]) => {
+                Box::new(Rect::try_new(0, 0, *width, *height).map_err(|e| error(e.to_string()))?)
+            }
+            ("rect", [x, y, width, height]) => {
+                Box::new(Rect::try_new(*x, *y, *width, *height).map_err(|e| error(e.to_string()))?)
+            }
+            ("rect", _) => return Err(error(String::from("A rect needs a width and height, optionally after x and y"))),
+            (kind, _) => return Err(error(format!("Unknown shape '{}'", kind))),
+        };
+
+        shapes.push(NamedShape { name: name.to_string(), shape });
+    }
+
+    Ok(shapes)
+}
+
+pub fn total_area(shapes: &[NamedShape]) -> f64 {
+    shapes.iter().map(|s| s.shape.area()).sum()
+}
+
+// The first of the largest shapes if more than one share the biggest area.
+pub fn largest(shapes: &[NamedShape]) -> Option<&NamedShape> {
+    shapes.iter().fold(None, |best: Option<&NamedShape>, s| match best {
+        Some(best) if best.shape.area() >= s.shape.area() => Some(best),
+        _ => Some(s),
+    })
+}
+
+// Every pair of shapes that overlap, by position in the list.
+pub fn overlaps(shapes: &[NamedShape]) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+    for (i, a) in shapes.iter().enumerate() {
+        for (j, b) in shapes.iter().enumerate().skip(i + 1) {
+            if a.shape.overlaps(b.shape.as_ref()) {
+                pairs.push((i, j));
+            }
+        }
+    }
+    pairs
+}
+
+// A table where each row says which of the column shapes it can hold.
+pub fn containment_matrix(shapes: &[NamedShape]) -> String {
+    let width = shapes.iter().map(|s| s.name.chars().count()).max().unwrap_or(0).max(3);
+    let mut table = format!("{:width$}", "", width = width);
+
+    for s in shapes {
+        table.push_str(&format!("  {:width$}", s.name, width = width));
+    }
+
+    for a in shapes {
+        table.push_str(&format!("\n{:width$}", a.name, width = width));
+        for b in shapes {
+            let cell = if std::ptr::eq(a, b) {
+                "-"
+            } else if a.shape.can_hold(b.shape.as_ref()) {
+                "yes"
+            } else {
+                "no"
+            };
+            table.push_str(&format!("  {:width$}", cell, width = width));
+        }
+    }
+
+    // The padding on the last column isn't wanted.
+    table.lines().map(str::trim_end).collect::<Vec<&str>>().join("\n")
+}
+
+pub fn answer(query: Query, shapes: &[NamedShape]) -> String {
+    match query {
+        Query::TotalArea => format!("Total area: {}", total_area(shapes)),
+        Query::Largest => match largest(shapes) {
+            Some(s) => format!("Largest shape: {} ({}, area {})", s.name, s.shape.kind(), s.shape.area()),
+            None => String::from("Largest shape: none"),
+        },
+        Query::Containment => format!("Which shapes can hold which:\n{}", containment_matrix(shapes)),
+        Query::Overlaps => {
+            let pairs = overlaps(shapes);
+            if pairs.is_empty() {
+                return String::from("No shapes overlap");
+            }
+
+            let lines: Vec<String> = pairs
+                .iter()
+                .map(|&(i, j)| format!("{} overlaps {}", shapes[i].name, shapes[j].name))
+                .collect();
+            lines.join("\n")
+        }
+    }
+}
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    let contents = fs::read_to_string(&config.filepath)?;
+    let shapes = parse_shapes(&contents)?;
+
+    for query in config.queries {
+        println!("{}", answer(query, &shapes));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod calculator_tests {
+    use super::*;
+
+    const SHAPES: &str = "\
+# kind, name, x, y, width, height
+rect, door, 0, 0, 3, 7
+
+rect window 4 2 2 2
+rect, table, 5, 3
+";
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter().map(|arg| arg.to_string()).collect::<Vec<String>>().into_iter()
+    }
+
+    #[test]
+    fn test_parse_shapes() {
+        let shapes = parse_shapes(SHAPES).unwrap();
+        let names: Vec<&str> = shapes.iter().map(|s| s.name.as_str()).collect();
+
+        assert_eq!(names, vec!["door", "window", "table"]);
+        assert_eq!(shapes[1].shape.bounds(), Rect::new(4, 2, 2, 2));
+        assert_eq!(shapes[2].shape.bounds(), Rect::build(5, 3));
+    }
+
+    #[test]
+    fn test_parse_errors_give_line() {
+        let error = parse_shapes("rect, a, 1, 1\ncircle, b, 3").unwrap_err();
+        assert_eq!(error, ParseError { line: 2, message: String::from("Unknown shape 'circle'") });
+
+        assert_eq!(parse_shapes("rect, a, 1").unwrap_err().line, 1);
+        assert_eq!(parse_shapes("\nrect, a, 1, x").unwrap_err().to_string(), "Line 2: 'x' is not a whole number");
+        assert_eq!(parse_shapes("rect").unwrap_err().line, 1);
+        assert_eq!(
+            parse_shapes("rect, a, 0, 0, -2147483648, 1").unwrap_err().to_string(),
+            "Line 1: Result does not fit in an i32!"
+        );
+        assert_eq!(
+            parse_shapes("rect, a, -2147483648, 1").unwrap_err().to_string(),
+            "Line 1: Result does not fit in an i32!"
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        let shapes = parse_shapes(SHAPES).unwrap();
+
+        assert_eq!(answer(Query::TotalArea, &shapes), "Total area: 40");
+        assert_eq!(answer(Query::Largest, &shapes), "Largest shape: door (rect, area 21)");
+        assert_eq!(answer(Query::Overlaps, &shapes), "door overlaps table\nwindow overlaps table");
+    }
+
+    #[test]
+    fn test_containment_matrix() {
+        let shapes = parse_shapes(SHAPES).unwrap();
+
+        let expected = [
+            "        door    window  table",
+            "door    -       yes     no",
+            "window  no      -       no",
+            "table   no      yes     -",
+        ];
+
+        assert_eq!(containment_matrix(&shapes), expected.join("\n"));
+    }
+
+    #[test]
+    fn test_config() {
+        let config = Config::new(args(&["shapes", "file.csv", "largest", "overlaps"])).unwrap();
+        assert_eq!(config.filepath, "file.csv");
+        assert_eq!(config.queries, vec![Query::Largest, Query::Overlaps]);
+
+        assert_eq!(Config::new(args(&["shapes", "file.csv"])).unwrap().queries, Query::ALL.to_vec());
+        assert!(Config::new(args(&["shapes"])).is_err());
+        assert!(Config::new(args(&["shapes", "file.csv", "volume"])).is_err());
+    }
+}
@@ -0,0 +1,15 @@
+// The Rect struct from this section's program, grown into a small geometry
+// library. main.rs still demonstrates the original methods.
+
+pub mod calculator;
+mod packing;
+mod quadtree;
+mod rect;
+pub mod render;
+mod shape;
+
+pub use packing::{pack, Container, PackError, Packing, Placement};
+pub use quadtree::QuadTree;
+pub use rect::{Rect, RectError};
+pub use render::Drawn;
+pub use shape::Shape;
@@ -1,54 +1,30 @@
 // Program to calculate the area of a rectangle
+// The Rect struct and its methods now live in rect.rs, see lib.rs.
+//
+// Given a file of shapes it works as a calculator instead, e.g.
+//     cargo run -- shapes.csv largest overlaps
+// See calculator.rs for the file format and queries.
 
-// Struct to store rectangle dimensions in pixels
-// Add the annotation #[derive(Debug)] to automatically generate debugging 
-// formatting for printing this struct.
-#[derive(Debug)]
-struct Rect {
-    width: i32,
-    height: i32,
-}
+use std::env;
+use std::process;
 
-// Define methods for Rect struct
-// Methods borrow the Rect object using the &self argument
-impl Rect {
+use struct_example_program::calculator::{self, Config};
+use struct_example_program::{pack, Container, Rect};
 
-    // Define a constructor for a Rect object
-    // Uses the struct init syntax in a useful way
-    // Maybe I am coming around to the Rust function return syntax a bit
-    fn build(width: i32, height: i32) -> Rect {
-        Rect {
-            width,
-            height,
-        }
-    }
-
-    // Define a function to calculate the area of a Rect object
-    // Takes the rect as a reference
-    fn area(&self) -> i32 {
-        return self.width * self.height;
-    }
-
-    // Define a function to print the details of a Rect object
-    // Includes call to area to print area
-    fn print_details(&self) {
-        println!(
-            "Width: {}, Height: {}, Area: {}", 
-            self.width, 
-            self.height, 
-            self.area(),
-        );
-    }
+fn main() {
+    if env::args().len() > 1 {
+        let config = Config::new(env::args()).unwrap_or_else(|err| {
+            println!("Problem parsing arguments: {}", err);
+            process::exit(1);
+        });
 
-    // Define a function to determine whether the called Rect can
-    // fit inside another given as a method argument.
-    fn can_hold(&self, other_rect: &Rect) -> bool {
-        self.width > other_rect.width && self.height > other_rect.height
+        if let Err(e) = calculator::run(config) {
+            println!("Application Error: {}", e);
+            process::exit(1);
+        }
+        return;
     }
-}
-
 
-fn main() {
     // Define a rectangle and print its details
     let rect1 = Rect::build(3, 5); 
     rect1.print_details();
@@ -64,4 +40,27 @@ fn main() {
 
     println!("rect1 can hold rect2: {}", rect1.can_hold(&rect2));
     println!("rect2 can hold rect1: {}", rect2.can_hold(&rect1));
-}
\ No newline at end of file
+
+    // Rects also have a position now, so we can ask where they overlap.
+    let rect3 = Rect::new(2, 3, 4, 4);
+    rect3.print_details();
+
+    println!("rect1 intersects rect3: {}", rect1.intersects(&rect3));
+    println!("Overlap of rect1 and rect3: {:?}", rect1.intersection(&rect3));
+    println!("Bounding box of rect1 and rect3: {:?}", rect1.union(&rect3));
+
+    // Pack all three into a strip 8 pixels wide, turning them if it helps.
+    match pack(&[rect1, rect2, rect3], Container::Strip { width: 8 }, true) {
+        Ok(packing) => {
+            for placement in &packing.placements {
+                println!("rect{} goes at {:?}", placement.index + 1, placement.rect);
+            }
+            println!(
+                "Strip height: {}, utilisation: {:.1}%",
+                packing.bin_height,
+                packing.utilisation() * 100.0,
+            );
+        }
+        Err(e) => println!("Could not pack: {}", e),
+    }
+}
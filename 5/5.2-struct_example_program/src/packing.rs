@@ -0,0 +1,359 @@
+// Packing Rects into a container without any of them overlapping, e.g. to
+// build a sprite atlas. can_hold answers whether one Rect fits inside
+// another; this places a whole set of them.
+//
+// It uses the MaxRects algorithm. Each bin keeps a list of the largest free
+// rectangles left in it. These are allowed to overlap each other, so the
+// list describes every free space exactly. A Rect is placed in the corner of
+// whichever free rectangle suits it best, then every free rectangle it
+// covers is split into the (up to four) pieces around it, and any piece that
+// lies inside another one is thrown away.
+//
+// Larger Rects are placed first as they are the hardest to fit in later.
+
+use std::error::Error;
+use std::fmt;
+
+use crate::Rect;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Container {
+    // A strip of fixed width that grows downwards as far as needed.
+    // Placements are kept as high up as possible so the strip stays short.
+    Strip { width: i32 },
+    // As many bins of this size as it takes.
+    Bins { width: i32, height: i32 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum PackError {
+    InvalidContainer,
+    // The Rect at this index doesn't fit in an empty container.
+    TooBig(usize),
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackError::InvalidContainer => write!(f, "The container needs a positive width and height!"),
+            PackError::TooBig(index) => write!(f, "Rect {} is too big for the container!", index),
+        }
+    }
+}
+
+impl Error for PackError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Placement {
+    // Index of the Rect in the slice given to pack.
+    pub index: usize,
+    pub bin: usize,
+    // Where it went, relative to the top left of its bin.
+    pub rect: Rect,
+    pub rotated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Packing {
+    // In the same order as the Rects given to pack.
+    pub placements: Vec<Placement>,
+    pub bins: usize,
+    // The size of each bin. For a strip this is its width and the height it
+    // ended up needing.
+    pub bin_width: i32,
+    pub bin_height: i32,
+}
+
+impl Packing {
+    // The area of one bin, for drawing it with the space left over.
+    pub fn bin_rect(&self) -> Rect {
+        Rect::build(self.bin_width, self.bin_height)
+    }
+
+    pub fn used_area(&self) -> i64 {
+        self.placements
+            .iter()
+            .map(|p| p.rect.width as i64 * p.rect.height as i64)
+            .sum()
+    }
+
+    pub fn total_area(&self) -> i64 {
+        self.bins as i64 * self.bin_width as i64 * self.bin_height as i64
+    }
+
+    // The fraction of the bins covered by Rects, from 0 to 1.
+    pub fn utilisation(&self) -> f64 {
+        match self.total_area() {
+            0 => 0.0,
+            total => self.used_area() as f64 / total as f64,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Heuristic {
+    // Fill from the top left, for strips.
+    BottomLeft,
+    // The free rectangle with the least space left over along its shorter
+    // side, for fixed size bins.
+    BestShortSideFit,
+}
+
+struct Bin {
+    free: Vec<Rect>,
+}
+
+impl Bin {
+    fn new(width: i32, height: i32) -> Bin {
+        Bin { free: vec![Rect::new(0, 0, width, height)] }
+    }
+
+    // The best spot for a width x height Rect as (score, placed rect,
+    // rotated). Lower scores are better.
+    // is_none_or would need Rust 1.82, which nothing else here does.
+    #[allow(clippy::unnecessary_map_or)]
+    fn find(&self, width: i32, height: i32, rotate: bool, heuristic: Heuristic) -> Option<((i64, i64), Rect, bool)> {
+        let mut sizes = vec![(width, height, false)];
+        if rotate && width != height {
+            sizes.push((height, width, true));
+        }
+
+        let mut best = None;
+        for free in &self.free {
+            for &(w, h, rotated) in &sizes {
+                if !free.can_hold_inclusive(&Rect::build(w, h)) {
+                    continue;
+                }
+
+                let score = match heuristic {
+                    Heuristic::BottomLeft => (free.y as i64 + h as i64, free.x as i64),
+                    Heuristic::BestShortSideFit => {
+                        let leftover_w = (free.width - w) as i64;
+                        let leftover_h = (free.height - h) as i64;
+                        (leftover_w.min(leftover_h), leftover_w.max(leftover_h))
+                    }
+                };
+
+                if best.as_ref().map_or(true, |(best_score, _, _)| score < *best_score) {
+                    best = Some((score, Rect::new(free.x, free.y, w, h), rotated));
+                }
+            }
+        }
+
+        best
+    }
+
+    fn place(&mut self, used: &Rect) {
+        let mut free = Vec::with_capacity(self.free.len() + 4);
+
+        for rect in &self.free {
+            if !rect.intersects(used) {
+                free.push(*rect);
+                continue;
+            }
+
+            // Everything in bins fits in an i32 so the far edges are safe to
+            // narrow back down.
+            let (right, bottom) = (rect.right_wide() as i32, rect.bottom_wide() as i32);
+            let (used_right, used_bottom) = (used.right_wide() as i32, used.bottom_wide() as i32);
+
+            if used.x > rect.x {
+                free.push(Rect::new(rect.x, rect.y, used.x - rect.x, rect.height));
+            }
+            if used_right < right {
+                free.push(Rect::new(used_right, rect.y, right - used_right, rect.height));
+            }
+            if used.y > rect.y {
+                free.push(Rect::new(rect.x, rect.y, rect.width, used.y - rect.y));
+            }
+            if used_bottom < bottom {
+                free.push(Rect::new(rect.x, used_bottom, rect.width, bottom - used_bottom));
+            }
+        }
+
+        // Drop free rectangles that are inside another. Of two identical ones
+        // only the first is kept.
+        let mut pruned: Vec<Rect> = Vec::with_capacity(free.len());
+        for (i, rect) in free.iter().enumerate() {
+            let covered = free
+                .iter()
+                .enumerate()
+                .any(|(j, other)| i != j && other.contains_rect(rect) && (other != rect || j < i));
+            if !covered {
+                pruned.push(*rect);
+            }
+        }
+
+        self.free = pruned;
+    }
+}
+
+/// Pack the Rects into the container, turning them by 90 degrees where it
+/// helps if allow_rotation is set. The positions of the given Rects are
+/// ignored, only their sizes matter.
+pub fn pack(rects: &[Rect], container: Container, allow_rotation: bool) -> Result<Packing, PackError> {
+    let (bin_width, bin_height, heuristic) = match container {
+        Container::Strip { width } => {
+            // The strip can't need to be taller than every Rect stacked up.
+            let stacked: i64 = rects.iter().map(|r| r.width.max(r.height) as i64).sum();
+            (width, stacked.clamp(1, i32::MAX as i64) as i32, Heuristic::BottomLeft)
+        }
+        Container::Bins { width, height } => (width, height, Heuristic::BestShortSideFit),
+    };
+
+    if bin_width <= 0 || bin_height <= 0 {
+        return Err(PackError::InvalidContainer);
+    }
+
+    let mut order: Vec<usize> = (0..rects.len()).collect();
+    order.sort_by_key(|&i| {
+        let rect = &rects[i];
+        std::cmp::Reverse((rect.width.max(rect.height), rect.width as i64 * rect.height as i64))
+    });
+
+    let mut bins = vec![Bin::new(bin_width, bin_height)];
+    let mut placements = Vec::with_capacity(rects.len());
+
+    for index in order {
+        let rect = &rects[index];
+
+        // Something with no area fits anywhere and takes up no space.
+        if rect.is_empty() {
+            let placed = Rect::new(0, 0, rect.width, rect.height);
+            placements.push(Placement { index, bin: 0, rect: placed, rotated: false });
+            continue;
+        }
+
+        let found = bins.iter().enumerate().find_map(|(bin, b)| {
+            b.find(rect.width, rect.height, allow_rotation, heuristic)
+                .map(|(_, placed, rotated)| (bin, placed, rotated))
+        });
+
+        let (bin, placed, rotated) = match found {
+            Some(found) => found,
+            None => {
+                let empty = Bin::new(bin_width, bin_height);
+                let (_, placed, rotated) = empty
+                    .find(rect.width, rect.height, allow_rotation, heuristic)
+                    .ok_or(PackError::TooBig(index))?;
+                bins.push(empty);
+                (bins.len() - 1, placed, rotated)
+            }
+        };
+
+        bins[bin].place(&placed);
+        placements.push(Placement { index, bin, rect: placed, rotated });
+    }
+
+    placements.sort_by_key(|p| p.index);
+
+    let bin_height = match container {
+        Container::Strip { .. } => placements
+            .iter()
+            .map(|p| p.rect.y + p.rect.height)
+            .max()
+            .unwrap_or(0),
+        Container::Bins { .. } => bin_height,
+    };
+
+    Ok(Packing { placements, bins: bins.len(), bin_width, bin_height })
+}
+
+#[cfg(test)]
+mod packing_tests {
+    use super::*;
+
+    fn assert_valid(rects: &[Rect], packing: &Packing) {
+        assert_eq!(packing.placements.len(), rects.len());
+        let bin = Rect::build(packing.bin_width, packing.bin_height);
+
+        for (i, placement) in packing.placements.iter().enumerate() {
+            let size = if placement.rotated {
+                Rect::build(rects[i].height, rects[i].width)
+            } else {
+                Rect::build(rects[i].width, rects[i].height)
+            };
+
+            assert_eq!(placement.index, i);
+            assert_eq!((placement.rect.width, placement.rect.height), (size.width, size.height));
+            assert!(bin.contains_rect(&placement.rect), "{:?} is outside the bin", placement);
+
+            for other in &packing.placements[i + 1..] {
+                assert!(
+                    placement.bin != other.bin || !placement.rect.intersects(&other.rect),
+                    "{:?} overlaps {:?}",
+                    placement,
+                    other,
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_perfect_fit() {
+        let rects = vec![Rect::build(2, 2); 4];
+        let packing = pack(&rects, Container::Bins { width: 4, height: 4 }, false).unwrap();
+
+        assert_valid(&rects, &packing);
+        assert_eq!(packing.bins, 1);
+        assert_eq!(packing.utilisation(), 1.0);
+    }
+
+    #[test]
+    fn test_strip_height_is_minimal_for_simple_rows() {
+        let rects = vec![Rect::build(5, 3), Rect::build(5, 3), Rect::build(10, 2)];
+        let packing = pack(&rects, Container::Strip { width: 10 }, false).unwrap();
+
+        assert_valid(&rects, &packing);
+        assert_eq!(packing.bin_height, 5);
+        assert_eq!(packing.used_area(), 50);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let rects = vec![Rect::build(1, 4)];
+
+        assert_eq!(
+            pack(&rects, Container::Bins { width: 4, height: 1 }, false),
+            Err(PackError::TooBig(0))
+        );
+
+        let packing = pack(&rects, Container::Bins { width: 4, height: 1 }, true).unwrap();
+        assert!(packing.placements[0].rotated);
+        assert_valid(&rects, &packing);
+    }
+
+    #[test]
+    fn test_extra_bins_are_opened() {
+        let rects = vec![Rect::build(3, 3); 5];
+        let packing = pack(&rects, Container::Bins { width: 4, height: 4 }, false).unwrap();
+
+        assert_valid(&rects, &packing);
+        assert_eq!(packing.bins, 5);
+    }
+
+    #[test]
+    fn test_many_mixed_rects() {
+        let rects: Vec<Rect> = (0..60)
+            .map(|i| Rect::build(1 + (i * 7) % 11, 1 + (i * 5) % 9))
+            .collect();
+
+        for &rotate in &[false, true] {
+            let strip = pack(&rects, Container::Strip { width: 32 }, rotate).unwrap();
+            assert_valid(&rects, &strip);
+            assert!(strip.utilisation() > 0.7, "only {}", strip.utilisation());
+
+            let bins = pack(&rects, Container::Bins { width: 16, height: 16 }, rotate).unwrap();
+            assert_valid(&rects, &bins);
+        }
+    }
+
+    #[test]
+    fn test_invalid_and_empty() {
+        assert_eq!(pack(&[], Container::Strip { width: 0 }, false), Err(PackError::InvalidContainer));
+
+        let packing = pack(&[Rect::build(0, 3)], Container::Strip { width: 4 }, false).unwrap();
+        assert_eq!(packing.used_area(), 0);
+        assert_eq!(packing.utilisation(), 0.0);
+    }
+}
@@ -0,0 +1,349 @@
+// A quadtree for finding Rects quickly when there are thousands of them.
+// Checking every pair with intersects or can_hold is fine for a handful of
+// Rects but gets slow fast.
+//
+// Every node covers an area and splits into four quarters once it holds too
+// many Rects. A Rect is stored in the smallest node that completely contains
+// it, so a search can skip any quarter that can't have what it is looking
+// for. Rects that straddle a split line (or lie outside the tree's area
+// altogether) stay further up.
+//
+// Each Rect gets an id when it is inserted, which is how it is removed and
+// how searches report what they found.
+
+use crate::{Rect, RectError};
+
+// A node splits once it holds more than this many Rects...
+const MAX_ITEMS: usize = 8;
+// ...unless it is already this deep.
+const MAX_DEPTH: usize = 10;
+
+#[derive(Debug)]
+struct Node {
+    bounds: Rect,
+    depth: usize,
+    items: Vec<usize>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+impl Node {
+    fn new(bounds: Rect, depth: usize) -> Node {
+        Node { bounds, depth, items: Vec::new(), children: None }
+    }
+
+    // The quarter that completely contains the Rect, if any.
+    fn child_for(&mut self, rect: &Rect) -> Option<&mut Node> {
+        self.children
+            .as_mut()?
+            .iter_mut()
+            .find(|child| child.bounds.contains_rect(rect))
+    }
+
+    fn insert(&mut self, id: usize, rects: &[Option<Rect>]) {
+        let rect = rects[id].unwrap();
+        if let Some(child) = self.child_for(&rect) {
+            return child.insert(id, rects);
+        }
+
+        self.items.push(id);
+        if self.children.is_none() && self.items.len() > MAX_ITEMS {
+            self.split(rects);
+        }
+    }
+
+    fn split(&mut self, rects: &[Option<Rect>]) {
+        let Rect { x, y, width, height } = self.bounds;
+        if self.depth >= MAX_DEPTH || width < 2 || height < 2 {
+            return;
+        }
+
+        let (half_w, half_h) = (width / 2, height / 2);
+        let depth = self.depth + 1;
+        self.children = Some(Box::new([
+            Node::new(Rect::new(x, y, half_w, half_h), depth),
+            Node::new(Rect::new(x + half_w, y, width - half_w, half_h), depth),
+            Node::new(Rect::new(x, y + half_h, half_w, height - half_h), depth),
+            Node::new(Rect::new(x + half_w, y + half_h, width - half_w, height - half_h), depth),
+        ]));
+
+        // Move everything that now fits in a quarter down into it.
+        for id in std::mem::take(&mut self.items) {
+            self.insert(id, rects);
+        }
+    }
+
+    // Removal follows the same path insert took.
+    fn remove(&mut self, id: usize, rect: &Rect) -> bool {
+        if let Some(child) = self.child_for(rect) {
+            return child.remove(id, rect);
+        }
+
+        match self.items.iter().position(|&item| item == id) {
+            Some(i) => {
+                self.items.swap_remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Visit every Rect in the nodes that might_hold says are worth looking in.
+    fn search(&self, might_hold: &dyn Fn(&Rect) -> bool, found: &mut dyn FnMut(usize)) {
+        for &id in &self.items {
+            found(id);
+        }
+
+        if let Some(children) = &self.children {
+            for child in children.iter().filter(|child| might_hold(&child.bounds)) {
+                child.search(might_hold, found);
+            }
+        }
+    }
+
+    // Everything in a node is inside its bounds, so nothing in it can be
+    // closer than the bounds are. That lets whole quarters be skipped once
+    // something nearer has been found.
+    // Both checks use map_or because is_none_or is newer than the Rust these
+    // examples are written for.
+    #[allow(clippy::unnecessary_map_or)]
+    fn nearest(&self, px: i32, py: i32, rects: &[Option<Rect>], best: &mut Option<(u128, usize)>) {
+        for &id in &self.items {
+            let candidate = (rects[id].unwrap().distance_squared(px, py), id);
+            if best.map_or(true, |best| candidate < best) {
+                *best = Some(candidate);
+            }
+        }
+
+        if let Some(children) = &self.children {
+            let mut children: Vec<(u128, &Node)> = children
+                .iter()
+                .map(|child| (child.bounds.distance_squared(px, py), child))
+                .collect();
+            children.sort_by_key(|(distance, _)| *distance);
+
+            for (distance, child) in children {
+                // Equal distances are still searched in case the Rect there
+                // has a lower id.
+                if best.map_or(true, |(best_distance, _)| distance <= best_distance) {
+                    child.nearest(px, py, rects, best);
+                }
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct QuadTree {
+    root: Node,
+    // Indexed by id, None once removed.
+    rects: Vec<Option<Rect>>,
+    len: usize,
+}
+
+impl QuadTree {
+    // Rects don't have to be inside the bounds, but any that aren't are
+    // kept at the top of the tree and checked by every search.
+    pub fn new(bounds: Rect) -> Result<QuadTree, RectError> {
+        // Splitting works out the far edges, so they need to fit in an i32.
+        bounds.right()?;
+        bounds.bottom()?;
+
+        Ok(QuadTree { root: Node::new(bounds, 0), rects: Vec::new(), len: 0 })
+    }
+
+    pub fn insert(&mut self, rect: Rect) -> usize {
+        let id = self.rects.len();
+        self.rects.push(Some(rect));
+        self.root.insert(id, &self.rects);
+        self.len += 1;
+        id
+    }
+
+    pub fn remove(&mut self, id: usize) -> Option<Rect> {
+        let rect = self.rects.get_mut(id)?.take()?;
+        self.root.remove(id, &rect);
+        self.len -= 1;
+        Some(rect)
+    }
+
+    pub fn get(&self, id: usize) -> Option<&Rect> {
+        self.rects.get(id)?.as_ref()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The ids of every Rect containing the point, lowest first.
+    pub fn at_point(&self, px: i32, py: i32) -> Vec<usize> {
+        let mut ids = Vec::new();
+        self.root.search(&|bounds| bounds.contains_point(px, py), &mut |id| {
+            if self.rects[id].unwrap().contains_point(px, py) {
+                ids.push(id);
+            }
+        });
+
+        ids.sort_unstable();
+        ids
+    }
+
+    /// The ids of every Rect that overlaps the region, lowest first.
+    pub fn query(&self, region: &Rect) -> Vec<usize> {
+        let mut ids = Vec::new();
+        self.root.search(&|bounds| bounds.intersects(region), &mut |id| {
+            if self.rects[id].unwrap().intersects(region) {
+                ids.push(id);
+            }
+        });
+
+        ids.sort_unstable();
+        ids
+    }
+
+    /// The id of the Rect closest to the point. A point inside a Rect is
+    /// at distance 0, and ties go to the lowest id.
+    pub fn nearest(&self, px: i32, py: i32) -> Option<usize> {
+        let mut best = None;
+        self.root.nearest(px, py, &self.rects, &mut best);
+        best.map(|(_, id)| id)
+    }
+}
+
+#[cfg(test)]
+mod quadtree_tests {
+    use super::*;
+
+    // Enough Rects in the top left quarter of a 100 x 100 tree for it to
+    // split twice, along with ones that straddle the split lines, cover
+    // everything, have no area, are repeated or lie outside altogether.
+    const RECTS: [(i32, i32, i32, i32); 22] = [
+        (2, 2, 5, 5),
+        (10, 4, 6, 3),
+        (20, 20, 4, 4),
+        (30, 8, 8, 8),
+        (40, 40, 6, 6),
+        (5, 30, 3, 12),
+        (15, 15, 0, 0),
+        (44, 2, 4, 4),
+        (2, 44, 4, 4),
+        (25, 35, 10, 5),
+        (60, 10, 10, 10),
+        (80, 80, 5, 5),
+        (10, 70, 20, 10),
+        (55, 55, 30, 30),
+        (45, 45, 10, 10),
+        (0, 48, 100, 4),
+        (48, 0, 4, 100),
+        (0, 0, 100, 100),
+        (120, 120, 5, 5),
+        (-30, -30, 10, 10),
+        (90, -20, 20, 30),
+        (20, 20, 4, 4),
+    ];
+
+    fn build_tree() -> (QuadTree, Vec<Option<Rect>>) {
+        let mut tree = QuadTree::new(Rect::build(100, 100)).unwrap();
+        let mut rects = Vec::new();
+
+        for &(x, y, width, height) in RECTS.iter() {
+            let rect = Rect::new(x, y, width, height);
+            assert_eq!(tree.insert(rect), rects.len());
+            rects.push(Some(rect));
+        }
+
+        (tree, rects)
+    }
+
+    fn brute_force(rects: &[Option<Rect>], keep: impl Fn(&Rect) -> bool) -> Vec<usize> {
+        (0..rects.len())
+            .filter(|&id| rects[id].as_ref().is_some_and(&keep))
+            .collect()
+    }
+
+    fn brute_force_nearest(rects: &[Option<Rect>], px: i32, py: i32) -> Option<usize> {
+        (0..rects.len())
+            .filter_map(|id| rects[id].map(|rect| (rect.distance_squared(px, py), id)))
+            .min()
+            .map(|(_, id)| id)
+    }
+
+    // Every point on a grid a little wider than the tree, and every Rect
+    // (removed ones too) along with a few others as regions.
+    fn check_against_brute_force(tree: &QuadTree, rects: &[Option<Rect>]) {
+        for px in (-40..=140).step_by(3) {
+            for py in (-40..=140).step_by(3) {
+                assert_eq!(
+                    tree.at_point(px, py),
+                    brute_force(rects, |rect| rect.contains_point(px, py))
+                );
+                assert_eq!(tree.nearest(px, py), brute_force_nearest(rects, px, py));
+            }
+        }
+
+        let regions = RECTS
+            .iter()
+            .map(|&(x, y, width, height)| Rect::new(x, y, width, height))
+            .chain(vec![Rect::new(49, 49, 2, 2), Rect::new(-100, -100, 50, 50), Rect::new(0, 0, 1, 1)]);
+
+        for region in regions {
+            assert_eq!(
+                tree.query(&region),
+                brute_force(rects, |rect| rect.intersects(&region))
+            );
+        }
+    }
+
+    #[test]
+    fn test_matches_brute_force() {
+        let (tree, rects) = build_tree();
+
+        assert_eq!(tree.len(), RECTS.len());
+        // Make sure the tree really did split, or this proves nothing.
+        let top_left = &tree.root.children.as_ref().unwrap()[0];
+        assert!(top_left.children.is_some());
+        check_against_brute_force(&tree, &rects);
+    }
+
+    #[test]
+    fn test_matches_brute_force_after_removals() {
+        let (mut tree, mut rects) = build_tree();
+
+        for id in (0..RECTS.len()).step_by(3) {
+            assert_eq!(tree.remove(id), rects[id].take());
+        }
+
+        assert_eq!(tree.len(), 14);
+        assert_eq!(tree.remove(0), None);
+        check_against_brute_force(&tree, &rects);
+    }
+
+    #[test]
+    fn test_small_example() {
+        let mut tree = QuadTree::new(Rect::build(100, 100)).unwrap();
+        let a = tree.insert(Rect::new(10, 10, 20, 20));
+        let b = tree.insert(Rect::new(25, 25, 10, 10));
+        let outside = tree.insert(Rect::new(200, 200, 5, 5));
+
+        assert_eq!(tree.at_point(27, 27), vec![a, b]);
+        assert_eq!(tree.query(&Rect::new(190, 190, 20, 20)), vec![outside]);
+        assert_eq!(tree.nearest(50, 50), Some(b));
+        assert_eq!(tree.nearest(300, 300), Some(outside));
+
+        tree.remove(b);
+        assert_eq!(tree.get(b), None);
+        assert_eq!(tree.at_point(27, 27), vec![a]);
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let tree = QuadTree::new(Rect::build(10, 10)).unwrap();
+
+        assert!(tree.is_empty());
+        assert_eq!(tree.nearest(0, 0), None);
+        assert!(QuadTree::new(Rect::new(i32::MAX, 0, 1, 1)).is_err());
+    }
+}
@@ -0,0 +1,372 @@
+// An axis-aligned rectangle with integer coordinates in pixels.
+// (x, y) is the corner with the smallest coordinates and the rectangle
+// covers x <= px < x + width and y <= py < y + height. Treating the far
+// edges as outside means two rectangles that only touch don't overlap.
+//
+// Anything that could overflow an i32 uses checked arithmetic and returns a
+// RectError rather than wrapping around or panicking. The yes/no questions
+// (contains_point, intersects...) work in i64 internally so they can't
+// overflow at all.
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RectError {
+    Overflow,
+    NegativeScale,
+    Empty,
+}
+
+impl fmt::Display for RectError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RectError::Overflow => write!(f, "Result does not fit in an i32!"),
+            RectError::NegativeScale => write!(f, "Can't scale by a negative factor!"),
+            RectError::Empty => write!(f, "No rectangles given!"),
+        }
+    }
+}
+
+impl Error for RectError {}
+
+// Struct to store rectangle dimensions in pixels
+// Add the annotation #[derive(Debug)] to automatically generate debugging
+// formatting for printing this struct.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+// Define methods for Rect struct
+// Methods borrow the Rect object using the &self argument
+impl Rect {
+
+    // Define a constructor for a Rect object at the origin
+    // Uses the struct init syntax in a useful way
+    pub fn build(width: i32, height: i32) -> Rect {
+        Rect::new(0, 0, width, height)
+    }
+
+    // A negative width or height is turned around so that (x, y) is always
+    // the smallest corner. Panics if that doesn't fit in an i32, use try_new
+    // for sizes that come from outside the program.
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Rect {
+        match Rect::try_new(x, y, width, height) {
+            Ok(rect) => rect,
+            Err(e) => panic!("Rect::new({}, {}, {}, {}): {}", x, y, width, height, e),
+        }
+    }
+
+    // The same as new but reports a rectangle that can't be turned around,
+    // such as a width of i32::MIN which has no positive version, rather than
+    // making a different one.
+    pub fn try_new(x: i32, y: i32, width: i32, height: i32) -> Result<Rect, RectError> {
+        let flip = |start: i32, size: i32| -> Result<(i32, i32), RectError> {
+            if size >= 0 {
+                return Ok((start, size));
+            }
+            let start = start.checked_add(size).ok_or(RectError::Overflow)?;
+            let size = size.checked_neg().ok_or(RectError::Overflow)?;
+            Ok((start, size))
+        };
+        let (x, width) = flip(x, width)?;
+        let (y, height) = flip(y, height)?;
+
+        Ok(Rect { x, y, width, height })
+    }
+
+    pub fn right(&self) -> Result<i32, RectError> {
+        self.x.checked_add(self.width).ok_or(RectError::Overflow)
+    }
+
+    pub fn bottom(&self) -> Result<i32, RectError> {
+        self.y.checked_add(self.height).ok_or(RectError::Overflow)
+    }
+
+    // The far edges as i64, which can't overflow.
+    pub(crate) fn right_wide(&self) -> i64 {
+        self.x as i64 + self.width as i64
+    }
+
+    pub(crate) fn bottom_wide(&self) -> i64 {
+        self.y as i64 + self.height as i64
+    }
+
+    // Define a function to calculate the area of a Rect object
+    // Takes the rect as a reference
+    pub fn area(&self) -> Result<i32, RectError> {
+        self.width.checked_mul(self.height).ok_or(RectError::Overflow)
+    }
+
+    pub fn perimeter(&self) -> Result<i32, RectError> {
+        self.width
+            .checked_add(self.height)
+            .and_then(|half| half.checked_mul(2))
+            .ok_or(RectError::Overflow)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    // Define a function to print the details of a Rect object
+    // Includes call to area to print area
+    pub fn print_details(&self) {
+        let area = match self.area() {
+            Ok(area) => area.to_string(),
+            Err(e) => e.to_string(),
+        };
+
+        println!(
+            "Position: ({}, {}), Width: {}, Height: {}, Area: {}",
+            self.x,
+            self.y,
+            self.width,
+            self.height,
+            area,
+        );
+    }
+
+    // Define a function to determine whether the called Rect can
+    // fit inside another given as a method argument.
+    // This only compares sizes, not positions, and the other Rect has to be
+    // strictly smaller in both directions.
+    pub fn can_hold(&self, other_rect: &Rect) -> bool {
+        self.width > other_rect.width && self.height > other_rect.height
+    }
+
+    // The same as can_hold but a Rect of exactly the same size also fits.
+    pub fn can_hold_inclusive(&self, other_rect: &Rect) -> bool {
+        self.width >= other_rect.width && self.height >= other_rect.height
+    }
+
+    pub fn contains_point(&self, px: i32, py: i32) -> bool {
+        let (px, py) = (px as i64, py as i64);
+
+        self.x as i64 <= px && px < self.right_wide() && self.y as i64 <= py && py < self.bottom_wide()
+    }
+
+    // The squared distance from a point to the nearest part of the Rect, or
+    // 0 if the point is inside. Squared so it can stay in whole numbers, and
+    // u128 because the square of the distance between two far apart i32
+    // points doesn't fit in an i64.
+    pub fn distance_squared(&self, px: i32, py: i32) -> u128 {
+        let (px, py) = (px as i64, py as i64);
+        let dx = (self.x as i64 - px).max(px - self.right_wide()).max(0) as u128;
+        let dy = (self.y as i64 - py).max(py - self.bottom_wide()).max(0) as u128;
+
+        dx * dx + dy * dy
+    }
+
+    // Whether the other Rect lies completely inside this one where it is now.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        self.x <= other.x
+            && self.y <= other.y
+            && other.right_wide() <= self.right_wide()
+            && other.bottom_wide() <= self.bottom_wide()
+    }
+
+    // Rects overlap if they share some area. Touching edges don't count, and
+    // an empty Rect never overlaps anything.
+    pub fn intersects(&self, other: &Rect) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && (self.x as i64) < other.right_wide()
+            && (other.x as i64) < self.right_wide()
+            && (self.y as i64) < other.bottom_wide()
+            && (other.y as i64) < self.bottom_wide()
+    }
+
+    // The overlapping area of two Rects, if there is any.
+    // The result always fits in an i32 as it is inside both Rects.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        if !self.intersects(other) {
+            return None;
+        }
+
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        let right = self.right_wide().min(other.right_wide());
+        let bottom = self.bottom_wide().min(other.bottom_wide());
+
+        Some(Rect {
+            x,
+            y,
+            width: (right - x as i64) as i32,
+            height: (bottom - y as i64) as i32,
+        })
+    }
+
+    // The smallest Rect containing both, i.e. their bounding box.
+    pub fn union(&self, other: &Rect) -> Result<Rect, RectError> {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = self.right_wide().max(other.right_wide());
+        let bottom = self.bottom_wide().max(other.bottom_wide());
+
+        Ok(Rect {
+            x,
+            y,
+            width: i32::try_from(right - x as i64).map_err(|_| RectError::Overflow)?,
+            height: i32::try_from(bottom - y as i64).map_err(|_| RectError::Overflow)?,
+        })
+    }
+
+    pub fn bounding_box(rects: &[Rect]) -> Result<Rect, RectError> {
+        let (first, rest) = rects.split_first().ok_or(RectError::Empty)?;
+
+        rest.iter().try_fold(*first, |bounds, rect| bounds.union(rect))
+    }
+
+    pub fn translate(&self, dx: i32, dy: i32) -> Result<Rect, RectError> {
+        let x = self.x.checked_add(dx).ok_or(RectError::Overflow)?;
+        let y = self.y.checked_add(dy).ok_or(RectError::Overflow)?;
+        let moved = Rect { x, y, ..*self };
+
+        // The far edges have to fit too, or right() and bottom() would fail.
+        moved.right()?;
+        moved.bottom()?;
+        Ok(moved)
+    }
+
+    // Scale the size of the Rect, keeping (x, y) where it is.
+    pub fn scale(&self, factor_x: i32, factor_y: i32) -> Result<Rect, RectError> {
+        if factor_x < 0 || factor_y < 0 {
+            return Err(RectError::NegativeScale);
+        }
+
+        let width = self.width.checked_mul(factor_x).ok_or(RectError::Overflow)?;
+        let height = self.height.checked_mul(factor_y).ok_or(RectError::Overflow)?;
+        let scaled = Rect { width, height, ..*self };
+
+        scaled.right()?;
+        scaled.bottom()?;
+        Ok(scaled)
+    }
+}
+
+#[cfg(test)]
+mod rect_tests {
+    use super::*;
+
+    #[test]
+    fn test_new_normalises_negative_sizes() {
+        assert_eq!(Rect::new(10, 10, -4, -6), Rect::new(6, 4, 4, 6));
+        assert_eq!(Rect::build(3, 5), Rect { x: 0, y: 0, width: 3, height: 5 });
+    }
+
+    #[test]
+    fn test_try_new_boundaries() {
+        assert_eq!(Rect::try_new(0, 0, i32::MIN, 1), Err(RectError::Overflow));
+        assert_eq!(Rect::try_new(0, 0, 1, i32::MIN), Err(RectError::Overflow));
+        assert_eq!(Rect::try_new(i32::MIN, 0, -1, 1), Err(RectError::Overflow));
+        assert_eq!(Rect::try_new(i32::MIN + 1, 0, -1, 1), Ok(Rect::new(i32::MIN, 0, 1, 1)));
+        assert_eq!(Rect::try_new(-1, 0, -i32::MAX, 1), Ok(Rect::new(i32::MIN, 0, i32::MAX, 1)));
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn test_new_panics_rather_than_clamping() {
+        Rect::new(i32::MIN, 0, -1, 1);
+    }
+
+    #[test]
+    fn test_area_and_perimeter() {
+        let rect = Rect::build(3, 5);
+
+        assert_eq!(rect.area(), Ok(15));
+        assert_eq!(rect.perimeter(), Ok(16));
+    }
+
+    #[test]
+    fn test_overflow_is_reported() {
+        let huge = Rect::build(i32::MAX, 2);
+
+        assert_eq!(huge.area(), Err(RectError::Overflow));
+        assert_eq!(huge.perimeter(), Err(RectError::Overflow));
+        assert_eq!(huge.translate(1, 0), Err(RectError::Overflow));
+        assert_eq!(huge.scale(2, 1), Err(RectError::Overflow));
+        assert_eq!(Rect::new(i32::MAX, 0, 1, 1).right(), Err(RectError::Overflow));
+    }
+
+    #[test]
+    fn test_can_hold_strict_and_inclusive() {
+        let rect = Rect::build(4, 5);
+        let same = Rect::new(100, 100, 4, 5);
+
+        assert!(!rect.can_hold(&same));
+        assert!(rect.can_hold_inclusive(&same));
+        assert!(rect.can_hold(&Rect::build(3, 4)));
+        assert!(!rect.can_hold_inclusive(&Rect::build(5, 1)));
+    }
+
+    #[test]
+    fn test_contains_point_edges() {
+        let rect = Rect::new(1, 1, 2, 2);
+
+        assert!(rect.contains_point(1, 1));
+        assert!(rect.contains_point(2, 2));
+        assert!(!rect.contains_point(3, 2));
+        assert!(!rect.contains_point(0, 1));
+        assert!(Rect::new(i32::MAX - 1, 0, 1, 1).contains_point(i32::MAX - 1, 0));
+    }
+
+    #[test]
+    fn test_intersects_and_intersection() {
+        let a = Rect::new(0, 0, 4, 4);
+        let b = Rect::new(2, 1, 4, 4);
+        let touching = Rect::new(4, 0, 2, 2);
+
+        assert!(a.intersects(&b));
+        assert_eq!(a.intersection(&b), Some(Rect::new(2, 1, 2, 3)));
+        assert!(!a.intersects(&touching));
+        assert_eq!(a.intersection(&touching), None);
+        assert!(!a.intersects(&Rect::new(1, 1, 0, 2)));
+    }
+
+    #[test]
+    fn test_distance_squared() {
+        let rect = Rect::new(0, 0, 4, 4);
+
+        assert_eq!(rect.distance_squared(2, 2), 0);
+        assert_eq!(rect.distance_squared(7, 8), 9 + 16);
+        assert_eq!(rect.distance_squared(-1, 2), 1);
+        assert_eq!(Rect::new(i32::MIN, 0, 0, 0).distance_squared(i32::MAX, 0), (u32::MAX as u128).pow(2));
+    }
+
+    #[test]
+    fn test_contains_rect() {
+        let outer = Rect::new(0, 0, 10, 10);
+
+        assert!(outer.contains_rect(&Rect::new(2, 2, 8, 8)));
+        assert!(!outer.contains_rect(&Rect::new(2, 2, 9, 8)));
+    }
+
+    #[test]
+    fn test_union_and_bounding_box() {
+        let a = Rect::new(0, 0, 2, 2);
+        let b = Rect::new(5, -3, 1, 1);
+
+        assert_eq!(a.union(&b), Ok(Rect::new(0, -3, 6, 5)));
+        assert_eq!(Rect::bounding_box(&[a, b, Rect::new(-1, 0, 1, 10)]), Ok(Rect::new(-1, -3, 7, 13)));
+        assert_eq!(Rect::bounding_box(&[]), Err(RectError::Empty));
+        assert_eq!(
+            Rect::new(i32::MIN, 0, 1, 1).union(&Rect::new(i32::MAX - 1, 0, 1, 1)),
+            Err(RectError::Overflow)
+        );
+    }
+
+    #[test]
+    fn test_translate_and_scale() {
+        let rect = Rect::new(1, 2, 3, 4);
+
+        assert_eq!(rect.translate(-1, 5), Ok(Rect::new(0, 7, 3, 4)));
+        assert_eq!(rect.scale(2, 3), Ok(Rect::new(1, 2, 6, 12)));
+        assert_eq!(rect.scale(-1, 1), Err(RectError::NegativeScale));
+    }
+}
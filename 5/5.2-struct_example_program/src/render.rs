@@ -0,0 +1,233 @@
+// Drawing Rects so a layout can be checked by eye, either as an SVG file or
+// as a grid of characters that can be printed in a terminal.
+// print_details only gives the numbers, which doesn't show whether a packing
+// looks right.
+
+use crate::{Packing, Rect};
+
+// Used in turn for Rects that weren't given a colour.
+const PALETTE: [&str; 6] = ["#8dd3c7", "#ffffb3", "#bebada", "#fb8072", "#80b1d3", "#fdb462"];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drawn {
+    pub rect: Rect,
+    pub label: String,
+    pub colour: Option<String>,
+}
+
+impl Drawn {
+    pub fn new(rect: Rect, label: &str) -> Drawn {
+        Drawn { rect, label: label.to_string(), colour: None }
+    }
+
+    pub fn with_colour(mut self, colour: &str) -> Drawn {
+        self.colour = Some(colour.to_string());
+        self
+    }
+
+    // Label each Rect with its position in the list, starting from 1.
+    pub fn numbered(rects: &[Rect]) -> Vec<Drawn> {
+        rects
+            .iter()
+            .enumerate()
+            .map(|(i, rect)| Drawn::new(*rect, &(i + 1).to_string()))
+            .collect()
+    }
+
+    // The Rects packed into one bin, labelled with their index in the list
+    // that was packed.
+    pub fn from_packing(packing: &Packing, bin: usize) -> Vec<Drawn> {
+        packing
+            .placements
+            .iter()
+            .filter(|placement| placement.bin == bin)
+            .map(|placement| Drawn::new(placement.rect, &placement.index.to_string()))
+            .collect()
+    }
+}
+
+fn bounds(items: &[Drawn]) -> Option<Rect> {
+    let rects: Vec<Rect> = items.iter().map(|item| item.rect).collect();
+    Rect::bounding_box(&rects).ok()
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// An SVG document showing every Rect with its label in the middle.
+/// Coordinates are kept as they are and scale says how many SVG pixels to
+/// use for each one.
+pub fn svg(items: &[Drawn], scale: u32) -> String {
+    let view = bounds(items).unwrap_or_else(|| Rect::build(0, 0));
+    svg_in(view, items, scale)
+}
+
+/// The same as svg but showing the given area rather than just the part
+/// the Rects cover, e.g. a whole bin with the space left empty in it.
+pub fn svg_in(view: Rect, items: &[Drawn], scale: u32) -> String {
+    let mut document = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"{} {} {} {}\" width=\"{}\" height=\"{}\">\n",
+        view.x,
+        view.y,
+        view.width,
+        view.height,
+        view.width as i64 * scale as i64,
+        view.height as i64 * scale as i64,
+    );
+
+    for (i, item) in items.iter().enumerate() {
+        let Rect { x, y, width, height } = item.rect;
+        let colour = item.colour.as_deref().unwrap_or(PALETTE[i % PALETTE.len()]);
+
+        document.push_str(&format!(
+            "  <rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\" stroke=\"black\" \
+             stroke-width=\"1\" vector-effect=\"non-scaling-stroke\"/>\n",
+            x,
+            y,
+            width,
+            height,
+            escape_xml(colour),
+        ));
+
+        if !item.label.is_empty() {
+            document.push_str(&format!(
+                "  <text x=\"{}\" y=\"{}\" font-size=\"{}\" text-anchor=\"middle\" \
+                 dominant-baseline=\"middle\">{}</text>\n",
+                x as f64 + width as f64 / 2.0,
+                y as f64 + height as f64 / 2.0,
+                (width.min(height) / 2).max(1),
+                escape_xml(&item.label),
+            ));
+        }
+    }
+
+    document.push_str("</svg>\n");
+    document
+}
+
+/// A grid of characters with each Rect filled in with its own character,
+/// followed by a legend giving the label for each character. Empty space is
+/// '.' and anywhere Rects overlap is '#', so a bad packing stands out.
+/// Each character covers a cell_size x cell_size square and shows whatever
+/// covers the middle of it.
+pub fn ascii(items: &[Drawn], cell_size: u32) -> String {
+    match bounds(items) {
+        Some(view) => ascii_in(view, items, cell_size),
+        None => String::new(),
+    }
+}
+
+/// The same as ascii but showing the given area, see svg_in.
+pub fn ascii_in(view: Rect, items: &[Drawn], cell_size: u32) -> String {
+    let cell = cell_size.max(1) as i64;
+    let columns = (view.width as i64 + cell - 1) / cell;
+    let rows = (view.height as i64 + cell - 1) / cell;
+    let mut grid = String::with_capacity(((columns + 1) * rows) as usize);
+
+    for row in 0..rows {
+        for column in 0..columns {
+            // The view is a Rect so these fit in an i32 too.
+            let px = (view.x as i64 + column * cell + cell / 2) as i32;
+            let py = (view.y as i64 + row * cell + cell / 2) as i32;
+
+            let mut covering = (0..items.len()).filter(|&i| items[i].rect.contains_point(px, py));
+            let c = match (covering.next(), covering.next()) {
+                (None, _) => '.',
+                (Some(i), None) => glyph(i),
+                (Some(_), Some(_)) => '#',
+            };
+            grid.push(c);
+        }
+        grid.push('\n');
+    }
+
+    if !items.is_empty() {
+        grid.push('\n');
+    }
+    for (i, item) in items.iter().enumerate() {
+        let line = format!("{} {}", glyph(i), item.label);
+        grid.push_str(line.trim_end());
+        grid.push('\n');
+    }
+
+    grid
+}
+
+// Labels such as 1, 10 and 11 all start with the same character, so the grid
+// uses a character per Rect instead and the legend says which is which.
+// Past the 62nd Rect they all share '*'.
+const GLYPHS: &str = "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+fn glyph(index: usize) -> char {
+    GLYPHS.chars().nth(index).unwrap_or('*')
+}
+
+#[cfg(test)]
+mod render_tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_grid() {
+        let items = vec![
+            Drawn::new(Rect::new(0, 0, 3, 2), "a"),
+            Drawn::new(Rect::new(2, 1, 2, 2), "b"),
+            Drawn::new(Rect::new(5, 0, 1, 1), ""),
+        ];
+
+        assert_eq!(ascii(&items, 1), "000..2\n00#1..\n..11..\n\n0 a\n1 b\n2\n");
+    }
+
+    #[test]
+    fn test_ascii_labels_that_share_a_first_character() {
+        let items: Vec<Drawn> = (0..64).map(|i| Drawn::new(Rect::new(i, 0, 1, 1), &(i + 1).to_string())).collect();
+        let grid = ascii(&items, 1);
+        let mut lines = grid.lines();
+
+        assert_eq!(
+            lines.next(),
+            Some("0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ**")
+        );
+        assert_eq!(lines.next(), Some(""));
+        assert_eq!(lines.nth(10), Some("a 11"));
+        assert_eq!(lines.last(), Some("* 64"));
+    }
+
+    #[test]
+    fn test_ascii_cell_size() {
+        let items = vec![Drawn::new(Rect::new(10, 10, 4, 2), "x")];
+
+        assert_eq!(ascii(&items, 2), "00\n\n0 x\n");
+        assert_eq!(ascii(&[], 1), "");
+    }
+
+    #[test]
+    fn test_view_shows_empty_space() {
+        let items = vec![Drawn::new(Rect::new(1, 0, 2, 1), "a")];
+
+        assert_eq!(ascii_in(Rect::build(4, 2), &items, 1), ".00.\n....\n\n0 a\n");
+        assert!(svg_in(Rect::build(4, 2), &items, 10).contains("viewBox=\"0 0 4 2\" width=\"40\" height=\"20\""));
+    }
+
+    #[test]
+    fn test_svg_escapes_and_colours() {
+        let items = vec![Drawn::new(Rect::new(1, 2, 4, 6), "<a & b>").with_colour("red")];
+        let document = svg(&items, 10);
+
+        assert!(document.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" viewBox=\"1 2 4 6\" width=\"40\" height=\"60\">"));
+        assert!(document.contains("fill=\"red\""));
+        assert!(document.contains(">&lt;a &amp; b&gt;</text>"));
+        assert!(document.contains("x=\"3\" y=\"5\""));
+    }
+}
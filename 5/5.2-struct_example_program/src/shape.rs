@@ -0,0 +1,66 @@
+// A trait for anything the shape calculator can work with. Rect is the only
+// shape so far but circles and triangles are planned, so the calculator
+// only ever talks to shapes through this.
+//
+// Rect has its own area and can_hold methods, which are what rect.area()
+// and rect.can_hold() call. These versions are used when all that is known
+// is that something is a Shape.
+
+use std::fmt::Debug;
+
+use crate::Rect;
+
+pub trait Shape: Debug {
+    // A short name for the kind of shape, as written in shape files.
+    fn kind(&self) -> &'static str;
+
+    // An f64 so that it can't overflow and so that shapes with curves fit.
+    fn area(&self) -> f64;
+
+    // The smallest Rect that the whole shape fits in.
+    fn bounds(&self) -> Rect;
+
+    // Whether the two shapes share some area where they are now.
+    // Comparing bounds is exact for Rects; other shapes should do better.
+    fn overlaps(&self, other: &dyn Shape) -> bool {
+        self.bounds().intersects(&other.bounds())
+    }
+
+    // Whether the other shape would fit inside this one, going by size only
+    // and with room to spare, as Rect::can_hold does.
+    fn can_hold(&self, other: &dyn Shape) -> bool {
+        self.bounds().can_hold(&other.bounds())
+    }
+}
+
+impl Shape for Rect {
+    fn kind(&self) -> &'static str {
+        "rect"
+    }
+
+    fn area(&self) -> f64 {
+        self.width as f64 * self.height as f64
+    }
+
+    fn bounds(&self) -> Rect {
+        *self
+    }
+}
+
+#[cfg(test)]
+mod shape_tests {
+    use super::*;
+
+    #[test]
+    fn test_rect_as_shape() {
+        let big: Box<dyn Shape> = Box::new(Rect::build(i32::MAX, 4));
+        let small: Box<dyn Shape> = Box::new(Rect::new(1, 1, 2, 2));
+
+        // Too big for Rect::area but fine as an f64.
+        assert_eq!(big.area(), i32::MAX as f64 * 4.0);
+        assert!(big.can_hold(small.as_ref()));
+        assert!(!small.can_hold(big.as_ref()));
+        assert!(big.overlaps(small.as_ref()));
+        assert_eq!(small.kind(), "rect");
+    }
+}
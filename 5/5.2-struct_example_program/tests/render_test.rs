@@ -0,0 +1,63 @@
+// Golden file tests for the rendering. Each test compares its output with a
+// file in tests/golden. After a deliberate change to the output, run the
+// tests with UPDATE_GOLDEN=1 to rewrite the files, then look over the diff
+// before committing them.
+//
+// The comparing is done by the golden file helper from the test_organisation
+// crate, so golden files work the same way in every crate.
+#[path = "../../../11/11.3-test_organisation/tests/common/golden.rs"]
+mod golden;
+
+use golden::assert_golden;
+use struct_example_program::render::{ascii, ascii_in, svg, svg_in};
+use struct_example_program::{pack, Container, Drawn, Rect};
+
+fn sprites() -> Vec<Rect> {
+    vec![
+        Rect::build(6, 4),
+        Rect::build(3, 5),
+        Rect::build(4, 4),
+        Rect::build(8, 2),
+        Rect::build(2, 2),
+        Rect::build(5, 3),
+        Rect::build(2, 6),
+        Rect::build(3, 3),
+    ]
+}
+
+#[test]
+fn test_packed_strip_ascii() {
+    let packing = pack(&sprites(), Container::Strip { width: 12 }, true).unwrap();
+
+    assert_golden("packed_strip.txt", &ascii_in(packing.bin_rect(), &Drawn::from_packing(&packing, 0), 1));
+}
+
+#[test]
+fn test_packed_strip_svg() {
+    let packing = pack(&sprites(), Container::Strip { width: 12 }, true).unwrap();
+
+    assert_golden("packed_strip.svg", &svg_in(packing.bin_rect(), &Drawn::from_packing(&packing, 0), 20));
+}
+
+#[test]
+fn test_packed_bins_show_empty_space() {
+    let packing = pack(&sprites(), Container::Bins { width: 10, height: 10 }, false).unwrap();
+
+    for bin in 0..packing.bins {
+        let items = Drawn::from_packing(&packing, bin);
+        assert_golden(&format!("packed_bin_{}.txt", bin), &ascii_in(packing.bin_rect(), &items, 1));
+        assert_golden(&format!("packed_bin_{}.svg", bin), &svg_in(packing.bin_rect(), &items, 20));
+    }
+}
+
+#[test]
+fn test_overlapping_layout() {
+    let items = vec![
+        Drawn::new(Rect::new(0, 0, 10, 6), "Header").with_colour("#dddddd"),
+        Drawn::new(Rect::new(0, 6, 3, 10), "Sidebar"),
+        Drawn::new(Rect::new(2, 6, 8, 10), "Main"),
+    ];
+
+    assert_golden("overlapping.txt", &ascii(&items, 1));
+    assert_golden("overlapping.svg", &svg(&items, 10));
+}